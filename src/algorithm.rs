@@ -5,13 +5,15 @@ use std::hash::Hash;
 use std::ops::Add;
 use std::vec::Vec;
 
+pub type PrevMap<N, C> = HashMap<N, (C, HashSet<N>)>;
+
 pub fn a_star_ex<NodeType, TFn, NFn, HFn, CostType>(
     start: NodeType,
     target_test: TFn,
     neighbors: NFn,
     heuristic: HFn,
     exhaustive: bool,
-) -> Option<(CostType, HashMap<NodeType, (CostType, HashSet<NodeType>)>)>
+) -> Option<(CostType, PrevMap<NodeType, CostType>)>
 where
     NodeType: Clone + Hash + Ord,
     CostType: Ord + PartialOrd + Add + Zero + Clone,
@@ -21,7 +23,7 @@ where
 {
     let mut queue: BinaryHeap<(Reverse<CostType>, NodeType)> = BinaryHeap::new();
     let mut traversed: HashMap<NodeType, CostType> = HashMap::new();
-    let mut prev: HashMap<NodeType, (CostType, HashSet<NodeType>)> = HashMap::new();
+    let mut prev: PrevMap<NodeType, CostType> = HashMap::new();
     queue.push((Reverse(heuristic(&start)), start.clone()));
     traversed.insert(start, CostType::zero());
     let mut lowest_cost: Option<CostType> = None;
@@ -118,7 +120,7 @@ pub fn dijkstra_ex<N, TFn, NFn, C>(
     target_test: TFn,
     neighbors: NFn,
     exhaustive: bool,
-) -> Option<(C, HashMap<N, (C, HashSet<N>)>)>
+) -> Option<(C, PrevMap<N, C>)>
 where
     N: Clone + Hash + Ord,
     C: Ord + PartialOrd + Add + Zero + Clone,
@@ -127,3 +129,139 @@ where
 {
     a_star_ex(start, target_test, neighbors, |_| C::zero(), exhaustive)
 }
+
+// The state after step `first` + `period` was the same as after step `first`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Repeat {
+    pub first: usize,
+    pub period: usize,
+}
+
+impl Repeat {
+    // The earlier step whose state matches step n
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.first {
+            n
+        } else {
+            self.first + (n - self.first) % self.period
+        }
+    }
+}
+
+// Steps the state in place until it comes up a second time, keeping only
+// the keys of earlier states. States are told apart by key, which could be
+// the state itself or something cheaper like Grid::fingerprint. On finding
+// the repeat the state is left as it was after step first + period; gives
+// up (returning None) after max_steps.
+pub fn run_until_repeat<S, K, F, KFn>(
+    state: &mut S,
+    max_steps: usize,
+    mut step: F,
+    key: KFn,
+) -> Option<Repeat>
+where
+    K: Eq + Hash,
+    F: FnMut(&mut S),
+    KFn: Fn(&S) -> K,
+{
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    seen.insert(key(state), 0);
+    for n in 1..=max_steps {
+        step(state);
+        if let Some(first) = seen.insert(key(state), n) {
+            return Some(Repeat {
+                first,
+                period: n - first,
+            });
+        }
+    }
+    None
+}
+
+// Applies step to start until a state comes up a second time. Returns every
+// state seen (starting with start) along with where the repeat is.
+pub fn find_repeat_by_key<S, K, F, KFn>(start: S, mut step: F, key: KFn) -> (Vec<S>, Repeat)
+where
+    K: Eq + Hash,
+    F: FnMut(&S) -> S,
+    KFn: Fn(&S) -> K,
+{
+    let mut states = vec![start];
+    let repeat = run_until_repeat(
+        &mut states,
+        usize::MAX,
+        |states| states.push(step(states.last().unwrap())),
+        |states| key(states.last().unwrap()),
+    )
+    .unwrap();
+    // the last state is the repeat of states[repeat.first]
+    states.pop();
+    (states, repeat)
+}
+
+pub fn find_repeat<S, F>(start: S, step: F) -> (Vec<S>, Repeat)
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    find_repeat_by_key(start, step, |s| s.clone())
+}
+
+// The state after n steps, which can be far more than could be run, as long
+// as the states eventually go round in a cycle.
+pub fn state_after_by_key<S, K, F, KFn>(start: S, n: usize, step: F, key: KFn) -> S
+where
+    K: Eq + Hash,
+    F: FnMut(&S) -> S,
+    KFn: Fn(&S) -> K,
+{
+    let (mut states, repeat) = find_repeat_by_key(start, step, key);
+    states.swap_remove(repeat.equivalent(n))
+}
+
+pub fn state_after<S, F>(start: S, n: usize, step: F) -> S
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    state_after_by_key(start, n, step, |s| s.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_until_repeat() {
+        let step = |x: &mut u64| *x = (*x * *x + 1) % 1000;
+        let mut x = 3;
+        assert_eq!(run_until_repeat(&mut x, 5, step, |x| *x), None);
+        let mut x = 3;
+        let repeat = run_until_repeat(&mut x, 100, step, |x| *x).unwrap();
+        let mut y = 3;
+        for _ in 0..repeat.first {
+            step(&mut y);
+        }
+        assert_eq!(x, y);
+        assert_eq!(
+            repeat.equivalent(repeat.first + 3 * repeat.period + 1),
+            repeat.first + 1
+        );
+        assert_eq!(repeat.equivalent(1), 1);
+    }
+
+    #[test]
+    fn test_repeat() {
+        let step = |x: &u64| (x * x + 1) % 1000;
+        let (states, repeat) = find_repeat(3, step);
+        assert_eq!(states[..4], [3, 10, 101, 202]);
+        assert_eq!(states[repeat.first], step(states.last().unwrap()));
+        let mut x = 3;
+        for n in 0..100 {
+            assert_eq!(state_after(3, n, step), x);
+            x = step(&x);
+        }
+        let big = state_after(3, 1_000_000_000, step);
+        assert_eq!(big, states[repeat.equivalent(1_000_000_000)]);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::algorithm::{run_until_repeat, Repeat};
+use crate::coords::{Coord2D, HEX_NEIGHBORS, NEIGHBORS4, NEIGHBORS8};
+use crate::grid::Grid;
+use crate::infinite_grid::InfiniteGrid;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+// Cellular automata on Grid and InfiniteGrid. Rules get the current value of a
+// cell and the values of its neighbors, and return the cell's next value.
+// On a Grid, neighbors outside the grid are left out of the slice; on an
+// InfiniteGrid they have the default value.
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Neighborhood {
+    Four,
+    Eight,
+    // axial coordinates, see HEX_NEIGHBORS
+    Hex,
+}
+
+impl Neighborhood {
+    pub fn offsets(&self) -> &'static [Coord2D] {
+        match self {
+            Neighborhood::Four => &NEIGHBORS4,
+            Neighborhood::Eight => &NEIGHBORS8,
+            Neighborhood::Hex => &HEX_NEIGHBORS,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    fn step_into<F>(&self, next: &mut Self, hood: Neighborhood, rule: &F, buf: &mut Vec<T>)
+    where
+        F: Fn(&T, &[T]) -> T,
+    {
+        for y in self.y_bounds() {
+            for x in self.x_bounds() {
+                buf.clear();
+                buf.extend(
+                    self.neighbors_with((x, y), hood.offsets(), false)
+                        .map(|(_, v)| v.clone()),
+                );
+                next.set(x, y, rule(self.get_ref(x, y), buf));
+            }
+        }
+    }
+
+    pub fn step<F>(&self, hood: Neighborhood, rule: F) -> Self
+    where
+        F: Fn(&T, &[T]) -> T,
+    {
+        let mut next = self.clone();
+        self.step_into(&mut next, hood, &rule, &mut Vec::new());
+        next
+    }
+
+    pub fn run<F>(&mut self, hood: Neighborhood, generations: usize, rule: F)
+    where
+        F: Fn(&T, &[T]) -> T,
+    {
+        let mut back = self.clone();
+        let mut buf = Vec::new();
+        for _ in 0..generations {
+            self.step_into(&mut back, hood, &rule, &mut buf);
+            std::mem::swap(self, &mut back);
+        }
+    }
+
+    // Steps until a state comes up a second time, leaving the grid in that
+    // state. Gives up (returning None) after max_generations.
+    pub fn run_until_repeat<F>(
+        &mut self,
+        hood: Neighborhood,
+        max_generations: usize,
+        rule: F,
+    ) -> Option<Repeat>
+    where
+        T: Eq + Hash,
+        F: Fn(&T, &[T]) -> T,
+    {
+        let mut back = self.clone();
+        let mut buf = Vec::new();
+        run_until_repeat(
+            self,
+            max_generations,
+            |g| {
+                g.step_into(&mut back, hood, &rule, &mut buf);
+                std::mem::swap(g, &mut back);
+            },
+            |g| g.data().clone(),
+        )
+    }
+}
+
+// Only cells that are set or next to one that is set get updated, so the rule
+// should map a default cell with default neighbors to the default value.
+// Cells that come out as the default aren't stored.
+impl<T: Clone + PartialEq> InfiniteGrid<T> {
+    pub fn step<F>(&self, hood: Neighborhood, rule: F) -> Self
+    where
+        F: Fn(&T, &[T]) -> T,
+    {
+        let offsets = hood.offsets();
+        let candidates: HashSet<Coord2D> = self
+            .iter()
+            .flat_map(|(&c, _)| {
+                let c = Coord2D::from(c);
+                offsets.iter().map(move |o| c + *o).chain([c])
+            })
+            .collect();
+        let mut next = InfiniteGrid::new(self.default_val().clone());
+        next.flip_y(self.y_flipped());
+        let mut buf = Vec::with_capacity(offsets.len());
+        for c in candidates {
+            buf.clear();
+            buf.extend(offsets.iter().map(|o| self.get_ref_c(c + *o).clone()));
+            let val = rule(self.get_ref_c(c), &buf);
+            if val != *self.default_val() {
+                next.set_c(c, val);
+            }
+        }
+        next
+    }
+
+    pub fn run<F>(&mut self, hood: Neighborhood, generations: usize, rule: F)
+    where
+        F: Fn(&T, &[T]) -> T,
+    {
+        for _ in 0..generations {
+            *self = self.step(hood, &rule);
+        }
+    }
+
+    pub fn run_until_repeat<F>(
+        &mut self,
+        hood: Neighborhood,
+        max_generations: usize,
+        rule: F,
+    ) -> Option<Repeat>
+    where
+        T: Eq + Hash,
+        F: Fn(&T, &[T]) -> T,
+    {
+        let state = |g: &Self| {
+            let mut cells: Vec<((i64, i64), T)> = g.iter().map(|(c, v)| (*c, v.clone())).collect();
+            cells.sort_by_key(|(c, _)| *c);
+            cells
+        };
+        run_until_repeat(self, max_generations, |g| *g = g.step(hood, &rule), state)
+    }
+}
+
+// One generation of a sparse on/off automaton over any kind of coordinate,
+// e.g. Coord3D with neighbors26. The rule gets whether a cell is on and how
+// many of its neighbors are.
+pub fn step_set<C, N, I, F>(live: &HashSet<C>, neighbors: N, rule: F) -> HashSet<C>
+where
+    C: Copy + Eq + Hash,
+    N: Fn(&C) -> I,
+    I: IntoIterator<Item = C>,
+    F: Fn(bool, usize) -> bool,
+{
+    let mut counts: HashMap<C, usize> = live.iter().map(|c| (*c, 0)).collect();
+    for c in live {
+        for n in neighbors(c) {
+            *counts.entry(n).or_insert(0) += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|(c, count)| rule(live.contains(c), *count))
+        .map(|(c, _)| c)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::Coord3D;
+    use crate::test_util::lines;
+
+    fn life(cell: &bool, n: &[bool]) -> bool {
+        matches!((cell, n.iter().filter(|v| **v).count()), (true, 2) | (_, 3))
+    }
+
+    #[test]
+    fn test_grid() {
+        let input = lines(&[".....", "..#..", "..#..", "..#..", "....."]);
+        let mut grid = Grid::from_input_map(&input, false, 0, |c| c == '#');
+        let next = grid.step(Neighborhood::Eight, life);
+        assert_eq!(
+            next.format_str(|v| if v { "#".into() } else { ".".into() }),
+            ".....\n.....\n.###.\n.....\n.....\n"
+        );
+        let repeat = grid.run_until_repeat(Neighborhood::Eight, 10, life);
+        assert_eq!(
+            repeat,
+            Some(Repeat {
+                first: 0,
+                period: 2
+            })
+        );
+        assert_eq!(repeat.unwrap().equivalent(7), 1);
+        grid.run(Neighborhood::Eight, 3, life);
+        assert_eq!(grid.data(), next.data());
+
+        let mut empty = Grid::from_input_map(&[], false, 0, |c| c == '#');
+        empty.run(Neighborhood::Eight, 2, life);
+        assert_eq!(
+            empty.run_until_repeat(Neighborhood::Eight, 2, life),
+            Some(Repeat {
+                first: 0,
+                period: 1
+            })
+        );
+
+        // on a hex grid a cell with two live neighbors comes alive
+        let mut grid: Grid<bool> = Grid::new(0, 0, 2, 2, false);
+        grid.set(1, 0, true);
+        grid.set(0, 1, true);
+        let grid = grid.step(Neighborhood::Hex, |_, n| {
+            n.iter().filter(|v| **v).count() == 2
+        });
+        assert!(grid.get(1, 1) && grid.get(0, 0) && !grid.get(1, 0));
+    }
+
+    #[test]
+    fn test_infinite() {
+        // a glider comes back to the same shape one cell down and right
+        let input = lines(&[".#.", "..#", "###"]);
+        let mut grid =
+            InfiniteGrid::from_input(&input, false, |c, _, _| (c == '#').then_some(true));
+        grid.flip_y(true);
+        grid.run(Neighborhood::Eight, 4, life);
+        assert!(grid.y_flipped());
+        let mut cells: Vec<(i64, i64)> = grid.iter().map(|(c, _)| *c).collect();
+        cells.sort();
+        assert_eq!(cells, vec![(1, 3), (2, 1), (2, 3), (3, 2), (3, 3)]);
+        assert_eq!((grid.x_bounds(), grid.y_bounds()), (1..4, 1..4));
+        assert_eq!(grid.run_until_repeat(Neighborhood::Eight, 20, life), None);
+    }
+
+    #[test]
+    fn test_step_set() {
+        let mut live: HashSet<Coord3D> = [(1, 0, 0), (2, 1, 0), (0, 2, 0), (1, 2, 0), (2, 2, 0)]
+            .into_iter()
+            .map(Coord3D::from)
+            .collect();
+        for _ in 0..6 {
+            live = step_set(&live, Coord3D::neighbors26, |on, n| {
+                n == 3 || (on && n == 2)
+            });
+        }
+        assert_eq!(live.len(), 112);
+    }
+}
@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+use ya_advent_lib::scaffold::{scaffold, TEMPLATES};
+
+fn usage() -> ExitCode {
+    eprintln!("Usage: aoc-scaffold <template> <day> [--dir DIR] [--manifest PATH]");
+    eprintln!(
+        "Templates: {}",
+        TEMPLATES
+            .iter()
+            .map(|(n, _)| *n)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    ExitCode::FAILURE
+}
+
+fn main() -> ExitCode {
+    let mut dir = PathBuf::from("src");
+    let mut manifest = PathBuf::from("Cargo.toml");
+    let mut positional = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dir" => match args.next() {
+                Some(d) => dir = d.into(),
+                None => return usage(),
+            },
+            "--manifest" => match args.next() {
+                Some(m) => manifest = m.into(),
+                None => return usage(),
+            },
+            _ => positional.push(arg),
+        }
+    }
+    let [template, day] = positional.as_slice() else {
+        return usage();
+    };
+    let Ok(day) = day.trim_start_matches("day").parse::<u32>() else {
+        return usage();
+    };
+    match scaffold(template, day, &dir, &manifest) {
+        Ok(created) => {
+            for path in created {
+                println!("Created {}", path.display());
+            }
+            println!("Added day{day:02} to {}", manifest.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use crate::coords::{Coord2D, NEIGHBORS8};
+use crate::grid::Grid;
+use std::io::Write;
+use std::ops::{Index, Range};
+
+// A grid of bools stored as one bit per cell, for simulations too big to
+// keep as a Grid<bool>. Coordinates work the same as in Grid.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BitGrid {
+    // A Grid<()> holds no data, just the bounds and padding, so the layout
+    // and cell order are Grid's own
+    shape: Grid<()>,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(min_x: i64, min_y: i64, max_x: i64, max_y: i64, initial_val: bool) -> Self {
+        let mut inst = Self::with_shape(Grid::new(min_x, min_y, max_x, max_y, ()));
+        if initial_val {
+            inst.fill(true);
+        }
+        inst
+    }
+
+    fn with_shape(shape: Grid<()>) -> Self {
+        let words = vec![0; shape.data().len().div_ceil(64)];
+        Self { shape, words }
+    }
+
+    // Reads '#' as set and anything else as clear, the same as format()
+    pub fn from_input(input: &[String], default_val: bool, padding: i64) -> Self {
+        Self::from_input_map(input, default_val, padding, |c| c == '#')
+    }
+
+    pub fn from_input_map<F>(input: &[String], default_val: bool, padding: i64, mapfunc: F) -> Self
+    where
+        F: Fn(char) -> bool,
+    {
+        Self::from_grid(&Grid::from_input_map(input, default_val, padding, mapfunc))
+    }
+
+    pub fn from_grid(grid: &Grid<bool>) -> Self {
+        let mut inst = Self::with_shape(grid.map(|_| ()));
+        for (idx, v) in grid.iter().enumerate() {
+            inst.set_bit(idx, *v);
+        }
+        inst
+    }
+
+    pub fn to_grid(&self) -> Grid<bool> {
+        let mut grid = self.shape.map(|_| false);
+        for (idx, cell) in grid.iter_mut().enumerate() {
+            *cell = self.bit(idx);
+        }
+        grid
+    }
+
+    fn bit(&self, idx: usize) -> bool {
+        self.words[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    fn set_bit(&mut self, idx: usize, val: bool) {
+        if val {
+            self.words[idx / 64] |= 1 << (idx % 64);
+        } else {
+            self.words[idx / 64] &= !(1 << (idx % 64));
+        }
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> bool {
+        self.bit(self.shape.cell_index(x, y))
+    }
+
+    pub fn get_c<C>(&self, coord: C) -> bool
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.get(c.x, c.y)
+    }
+
+    pub fn try_get(&self, x: i64, y: i64) -> Option<bool> {
+        self.shape.try_cell_index(x, y).map(|idx| self.bit(idx))
+    }
+
+    pub fn try_get_c<C>(&self, coord: C) -> Option<bool>
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.try_get(c.x, c.y)
+    }
+
+    pub fn get_or_default(&self, x: i64, y: i64, default: bool) -> bool {
+        self.try_get(x, y).unwrap_or(default)
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, val: bool) {
+        let idx = self.shape.cell_index(x, y);
+        self.set_bit(idx, val);
+    }
+
+    pub fn set_c<C>(&mut self, coord: C, val: bool)
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.set(c.x, c.y, val);
+    }
+
+    // Hands the value back if the coordinate is out of bounds
+    pub fn try_set(&mut self, x: i64, y: i64, val: bool) -> Result<(), bool> {
+        let idx = self.shape.try_cell_index(x, y).ok_or(val)?;
+        self.set_bit(idx, val);
+        Ok(())
+    }
+
+    pub fn try_set_c<C>(&mut self, coord: C, val: bool) -> Result<(), bool>
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.try_set(c.x, c.y, val)
+    }
+
+    pub fn toggle(&mut self, x: i64, y: i64) {
+        let idx = self.shape.cell_index(x, y);
+        self.words[idx / 64] ^= 1 << (idx % 64);
+    }
+
+    pub fn fill(&mut self, val: bool) {
+        self.words.fill(if val { u64::MAX } else { 0 });
+        // keep the bits past the last cell clear so count() and Eq work
+        let used = self.shape.data().len() % 64;
+        if val && used != 0 {
+            *self.words.last_mut().unwrap() = (1 << used) - 1;
+        }
+    }
+
+    // Number of cells that are set
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn count_neighbors8<C>(&self, coord: C) -> usize
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        NEIGHBORS8
+            .iter()
+            .filter(|o| self.get_or_default(c.x + o.x, c.y + o.y, false))
+            .count()
+    }
+
+    pub fn contains_coord<C>(&self, coord: C) -> bool
+    where
+        C: Into<Coord2D>,
+    {
+        self.shape.contains_coord(coord)
+    }
+
+    pub fn x_bounds(&self) -> Range<i64> {
+        self.shape.x_bounds()
+    }
+
+    pub fn y_bounds(&self) -> Range<i64> {
+        self.shape.y_bounds()
+    }
+
+    pub fn x_bounds_orig(&self) -> Range<i64> {
+        self.shape.x_bounds_orig()
+    }
+
+    pub fn y_bounds_orig(&self) -> Range<i64> {
+        self.shape.y_bounds_orig()
+    }
+
+    pub fn iter_with_coord(&self) -> impl Iterator<Item = (bool, i64, i64)> + '_ {
+        self.shape
+            .iter_with_coord()
+            .enumerate()
+            .map(|(idx, (_, x, y))| (self.bit(idx), x, y))
+    }
+
+    pub fn dump_to_file<F>(&self, file: &mut dyn Write, formatter: F)
+    where
+        F: Fn(bool) -> char,
+    {
+        write!(file, "{}", self.format_str(|v| formatter(v).into())).unwrap();
+    }
+
+    pub fn print(&self) {
+        print!("{}", self.format());
+    }
+
+    pub fn print_str<F>(&self, formatter: F)
+    where
+        F: Fn(bool) -> String,
+    {
+        print!("{}", self.format_str(formatter));
+    }
+
+    // Set cells as '#' and clear ones as '.'
+    pub fn format(&self) -> String {
+        self.format_str(|v| if v { "#".into() } else { ".".into() })
+    }
+
+    pub fn format_str<F>(&self, formatter: F) -> String
+    where
+        F: Fn(bool) -> String,
+    {
+        self.shape.format_with(|_, x, y| formatter(self.get(x, y)))
+    }
+
+    pub fn find<F>(&self, predicate: F) -> Option<(i64, i64)>
+    where
+        F: Fn(bool, i64, i64) -> bool,
+    {
+        self.iter_with_coord()
+            .find(|(v, x, y)| predicate(*v, *x, *y))
+            .map(|(_, x, y)| (x, y))
+    }
+
+    pub fn for_each<F>(&self, mut callback: F)
+    where
+        F: FnMut(bool, i64, i64),
+    {
+        for (v, x, y) in self.iter_with_coord() {
+            callback(v, x, y);
+        }
+    }
+}
+
+// There's no IndexMut, as a single bit can't be borrowed mutably; use set.
+impl Index<Coord2D> for BitGrid {
+    type Output = bool;
+    fn index(&self, c: Coord2D) -> &bool {
+        if self.get(c.x, c.y) {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+impl Index<(i64, i64)> for BitGrid {
+    type Output = bool;
+    fn index(&self, (x, y): (i64, i64)) -> &bool {
+        &self[Coord2D::new(x, y)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::lines;
+
+    #[test]
+    fn test_bit_grid() {
+        let mut grid = BitGrid::from_input(&lines(&["#..#", ".##.", "#"]), false, 1);
+        assert_eq!((grid.x_bounds(), grid.y_bounds()), (-1..5, -1..4));
+        assert_eq!(grid.x_bounds_orig(), 0..4);
+        assert_eq!(grid.count(), 5);
+        assert!(grid.get(3, 0) && !grid.get(3, 1) && grid.get_c((0, 2)));
+        assert!(grid[(1, 1)] && !grid[Coord2D::new(0, 1)]);
+        assert_eq!(grid.count_neighbors8((1, 1)), 3);
+        assert_eq!(grid.try_get(5, 0), None);
+        assert_eq!(grid.try_get_c((4, 3)), Some(false));
+        assert_eq!(grid.try_set(5, 0, true), Err(true));
+        assert_eq!(grid.find(|v, x, _| v && x > 0), Some((3, 0)));
+        let mut seen = 0;
+        grid.for_each(|v, _, _| seen += v as usize);
+        assert_eq!(seen, 5);
+        grid.toggle(3, 0);
+        grid.try_set_c((-1, -1), true).unwrap();
+        assert_eq!(grid.format(), "#.....\n.#....\n..##..\n.#....\n......\n");
+        let mut out = Vec::new();
+        grid.dump_to_file(&mut out, |v| if v { 'x' } else { ' ' });
+        assert_eq!(out[..14], *b"x     \n x    \n");
+        assert_eq!(
+            grid.iter_with_coord().filter(|(v, _, _)| *v).nth(1),
+            Some((true, 0, 0))
+        );
+
+        let big = grid.to_grid();
+        assert_eq!(
+            big.format_str(|v| if v { "#".into() } else { ".".into() }),
+            grid.format()
+        );
+        assert_eq!(big.x_bounds_orig(), 0..4);
+        assert_eq!(BitGrid::from_grid(&big), grid);
+
+        let mut full = BitGrid::new(0, 0, 9, 9, true);
+        assert_eq!(full.count(), 100);
+        full.fill(false);
+        assert_eq!(full, BitGrid::new(0, 0, 9, 9, false));
+    }
+}
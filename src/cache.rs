@@ -0,0 +1,244 @@
+use crate::read::{input_from_iter, InputSource};
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// A local store of puzzle inputs and examples, laid out as
+//
+//   <root>/<year>/dayNN.input
+//   <root>/<year>/dayNN.testinput      (example 0)
+//   <root>/<year>/dayNN-<n>.testinput  (further examples)
+//
+// with a .checksum file next to each recording the FNV-1a hash of the
+// contents when it was imported. Nothing is ever fetched over the network;
+// files get in here by being imported from disk.
+pub struct InputCache {
+    root: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum CacheError {
+    Missing {
+        year: u32,
+        day: u32,
+        path: PathBuf,
+    },
+    ChecksumMismatch {
+        path: PathBuf,
+        expected: u64,
+        actual: u64,
+    },
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::Missing { year, day, path } => write!(
+                f,
+                "No cached file for {year} day {day} (expected {}). Import it with InputCache::import_input or import_example.",
+                path.display()
+            ),
+            CacheError::ChecksumMismatch {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} has changed since it was imported (checksum {actual:016x}, expected {expected:016x})",
+                path.display()
+            ),
+            CacheError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+        }
+    }
+}
+
+impl Error for CacheError {}
+
+pub fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl Default for InputCache {
+    fn default() -> Self {
+        Self::new(Self::default_root())
+    }
+}
+
+impl InputCache {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().into(),
+        }
+    }
+
+    // $AOC_CACHE_DIR if set, otherwise $XDG_CACHE_HOME/advent_of_code or
+    // ~/.cache/advent_of_code, falling back to .aoc_cache in the current dir.
+    pub fn default_root() -> PathBuf {
+        if let Some(dir) = std::env::var_os("AOC_CACHE_DIR") {
+            return dir.into();
+        }
+        std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))
+            .map(|dir| dir.join("advent_of_code"))
+            .unwrap_or_else(|| PathBuf::from(".aoc_cache"))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn input_path(&self, year: u32, day: u32) -> PathBuf {
+        self.root
+            .join(year.to_string())
+            .join(format!("day{day:02}.input"))
+    }
+
+    pub fn example_path(&self, year: u32, day: u32, idx: usize) -> PathBuf {
+        let name = if idx == 0 {
+            format!("day{day:02}.testinput")
+        } else {
+            format!("day{day:02}-{idx}.testinput")
+        };
+        self.root.join(year.to_string()).join(name)
+    }
+
+    fn checksum_path(path: &Path) -> PathBuf {
+        let mut p = path.as_os_str().to_owned();
+        p.push(".checksum");
+        p.into()
+    }
+
+    fn import(&self, src: &Path, dest: PathBuf) -> Result<PathBuf, CacheError> {
+        let data = fs::read(src).map_err(|e| CacheError::Io(src.into(), e))?;
+        if let Some(dir) = dest.parent() {
+            fs::create_dir_all(dir).map_err(|e| CacheError::Io(dir.into(), e))?;
+        }
+        fs::write(&dest, &data).map_err(|e| CacheError::Io(dest.clone(), e))?;
+        let sum_path = Self::checksum_path(&dest);
+        fs::write(&sum_path, format!("{:016x}\n", checksum(&data)))
+            .map_err(|e| CacheError::Io(sum_path, e))?;
+        Ok(dest)
+    }
+
+    pub fn import_input<P: AsRef<Path>>(
+        &self,
+        year: u32,
+        day: u32,
+        src: P,
+    ) -> Result<PathBuf, CacheError> {
+        self.import(src.as_ref(), self.input_path(year, day))
+    }
+
+    pub fn import_example<P: AsRef<Path>>(
+        &self,
+        year: u32,
+        day: u32,
+        idx: usize,
+        src: P,
+    ) -> Result<PathBuf, CacheError> {
+        self.import(src.as_ref(), self.example_path(year, day, idx))
+    }
+
+    pub fn has_input(&self, year: u32, day: u32) -> bool {
+        self.input_path(year, day).is_file()
+    }
+
+    // Reads a cached file, checking it against the checksum recorded when it
+    // was imported (if there is one).
+    fn read(&self, year: u32, day: u32, path: PathBuf) -> Result<String, CacheError> {
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(CacheError::Missing { year, day, path });
+            }
+            Err(e) => return Err(CacheError::Io(path, e)),
+        };
+        if let Ok(sum) = fs::read_to_string(Self::checksum_path(&path)) {
+            if let Ok(expected) = u64::from_str_radix(sum.trim(), 16) {
+                let actual = checksum(&data);
+                if actual != expected {
+                    return Err(CacheError::ChecksumMismatch {
+                        path,
+                        expected,
+                        actual,
+                    });
+                }
+            }
+        }
+        String::from_utf8(data)
+            .map_err(|e| CacheError::Io(path, io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+
+    pub fn input(&self, year: u32, day: u32) -> Result<String, CacheError> {
+        self.read(year, day, self.input_path(year, day))
+    }
+
+    pub fn example(&self, year: u32, day: u32, idx: usize) -> Result<String, CacheError> {
+        self.read(year, day, self.example_path(year, day, idx))
+    }
+
+    pub fn read_input<T: FromStr>(&self, year: u32, day: u32) -> Result<Vec<T>, CacheError>
+    where
+        <T as FromStr>::Err: Any,
+    {
+        let data = self.input(year, day)?;
+        Ok(input_from_iter(data.lines().map(|l| l.into())))
+    }
+
+    // An InputSource that looks in the cache for the given day, while still
+    // honoring the usual command line, $AOC_INPUT and stdin overrides.
+    pub fn input_source(&self, year: u32, day: u32) -> InputSource {
+        InputSource::new()
+            .dir(&self.root)
+            .pattern("{year}/day{day:02}.input")
+            .year(year)
+            .day(day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_cache() {
+        let tmp = TempDir::new("cache");
+        let cache = InputCache::new(tmp.join("cache"));
+        fs::write(tmp.join("src.txt"), "1\n2\n3\n").unwrap();
+
+        assert!(matches!(
+            cache.input(2023, 5),
+            Err(CacheError::Missing { day: 5, .. })
+        ));
+        let path = cache.import_input(2023, 5, tmp.join("src.txt")).unwrap();
+        assert_eq!(path, tmp.join("cache/2023/day05.input"));
+        assert_eq!(cache.read_input::<i64>(2023, 5).unwrap(), vec![1, 2, 3]);
+        assert_eq!(
+            cache.input_source(2023, 5).candidates()[0],
+            tmp.join("cache/2023/day05.input")
+        );
+
+        cache
+            .import_example(2023, 5, 1, tmp.join("src.txt"))
+            .unwrap();
+        assert!(cache
+            .example_path(2023, 5, 1)
+            .ends_with("day05-1.testinput"));
+        assert_eq!(cache.example(2023, 5, 1).unwrap(), "1\n2\n3\n");
+
+        fs::write(&path, "tampered").unwrap();
+        assert!(matches!(
+            cache.input(2023, 5),
+            Err(CacheError::ChecksumMismatch { .. })
+        ));
+    }
+}
@@ -69,6 +69,29 @@ pub struct Coord2D {
     pub x: i64,
     pub y: i64,
 }
+
+// Offsets to the neighbors of a cell, in reading order
+#[rustfmt::skip]
+pub const NEIGHBORS4: [Coord2D; 4] = [
+                           Coord2D { x: 0, y: -1 },
+    Coord2D { x: -1, y: 0 },                        Coord2D { x: 1, y: 0 },
+                           Coord2D { x: 0, y: 1 },
+];
+#[rustfmt::skip]
+pub const NEIGHBORS8: [Coord2D; 8] = [
+    Coord2D { x: -1, y: -1 }, Coord2D { x: 0, y: -1 }, Coord2D { x: 1, y: -1 },
+    Coord2D { x: -1, y: 0 },                           Coord2D { x: 1, y: 0 },
+    Coord2D { x: -1, y: 1 },  Coord2D { x: 0, y: 1 },  Coord2D { x: 1, y: 1 },
+];
+// Neighbors on a hex grid in axial coordinates, with x as q and y as r
+pub const HEX_NEIGHBORS: [Coord2D; 6] = [
+    Coord2D { x: 0, y: -1 },
+    Coord2D { x: 1, y: -1 },
+    Coord2D { x: -1, y: 0 },
+    Coord2D { x: 1, y: 0 },
+    Coord2D { x: -1, y: 1 },
+    Coord2D { x: 0, y: 1 },
+];
 impl Coord2D {
     pub fn new(x: i64, y: i64) -> Self {
         Coord2D { x, y }
@@ -79,27 +102,11 @@ impl Coord2D {
     pub fn y() -> Self {
         Coord2D { x: 0, y: 1 }
     }
-    #[rustfmt::skip]
     pub fn neighbors4(&self) -> Vec<Self> {
-        [
-                      Coord2D::new(0, -1),
-            Coord2D::new(-1, 0), Coord2D::new(1, 0),
-                      Coord2D::new(0, 1),
-        ]
-        .iter()
-        .map(|o| *self + *o)
-        .collect()
+        NEIGHBORS4.iter().map(|o| *self + *o).collect()
     }
-    #[rustfmt::skip]
     pub fn neighbors8(&self) -> Vec<Self> {
-        [
-            Coord2D::new(-1, -1), Coord2D::new(0, -1), Coord2D::new(1, -1),
-            Coord2D::new(-1, 0),                       Coord2D::new(1, 0),
-            Coord2D::new(-1, 1),  Coord2D::new(0, 1),  Coord2D::new(1, 1),
-        ]
-        .iter()
-        .map(|o| *self + *o)
-        .collect()
+        NEIGHBORS8.iter().map(|o| *self + *o).collect()
     }
     pub fn mdist_to(&self, other: &Self) -> i64 {
         (self.x - other.x).abs() + (self.y - other.y).abs()
@@ -262,6 +269,19 @@ impl Coord3D {
         .map(|o| *self + *o)
         .collect()
     }
+    pub fn neighbors26(&self) -> Vec<Self> {
+        let mut v = Vec::with_capacity(26);
+        for z in -1..=1 {
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    if (x, y, z) != (0, 0, 0) {
+                        v.push(*self + Coord3D::new(x, y, z));
+                    }
+                }
+            }
+        }
+        v
+    }
 }
 
 impl fmt::Display for Coord3D {
@@ -1,14 +1,16 @@
-use crate::coords::Coord2D;
+use crate::coords::{Coord2D, NEIGHBORS4, NEIGHBORS8};
 use itertools::Itertools;
 use std::cmp::max;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::io::Write;
-use std::iter::Iterator;
-use std::ops::Range;
+use std::iter::{Iterator, StepBy};
+use std::ops::{Index, IndexMut, Range};
 use std::slice::{Iter, IterMut};
 use std::vec::Vec;
 
-#[derive(Clone)]
-pub struct Grid<T: Copy> {
+#[derive(Clone, Debug)]
+pub struct Grid<T> {
     min_x: i64,
     min_y: i64,
     x_size: usize,
@@ -17,7 +19,513 @@ pub struct Grid<T: Copy> {
     padding: i64,
 }
 
-impl<T: Copy> Grid<T> {
+impl<T> Grid<T> {
+    pub fn new_with<F>(min_x: i64, min_y: i64, max_x: i64, max_y: i64, mut initfunc: F) -> Self
+    where
+        F: FnMut(i64, i64) -> T,
+    {
+        let x_size = (max_x - min_x + 1) as usize;
+        let y_size = (max_y - min_y + 1) as usize;
+        let data = (min_y..=max_y)
+            .cartesian_product(min_x..=max_x)
+            .map(|(y, x)| initfunc(x, y))
+            .collect();
+        Self {
+            min_x,
+            min_y,
+            x_size,
+            y_size,
+            data,
+            padding: 0,
+        }
+    }
+
+    // Where cell (x, y) is in data(); panics if it's out of bounds
+    pub fn cell_index(&self, x: i64, y: i64) -> usize {
+        assert!(x >= self.min_x && x < self.min_x + self.x_size as i64);
+        assert!(y >= self.min_y && y < self.min_y + self.y_size as i64);
+        let ux: usize = (x - self.min_x) as usize;
+        let uy: usize = (y - self.min_y) as usize;
+        uy * self.x_size + ux
+    }
+
+    // cell_index, or None if (x, y) is out of bounds
+    pub fn try_cell_index(&self, x: i64, y: i64) -> Option<usize> {
+        if self.x_bounds().contains(&x) && self.y_bounds().contains(&y) {
+            Some(self.cell_index(x, y))
+        } else {
+            None
+        }
+    }
+
+    pub fn get_ref(&self, x: i64, y: i64) -> &T {
+        &self.data[self.cell_index(x, y)]
+    }
+
+    pub fn get_ref_c<C>(&self, coord: C) -> &T
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.get_ref(c.x, c.y)
+    }
+
+    pub fn get_ref_xform(&self, x: i64, y: i64, xform: GridTransform) -> &T {
+        let (x, y) = self.apply_transform(x, y, xform);
+        self.get_ref(x, y)
+    }
+
+    pub fn get_mut(&mut self, x: i64, y: i64) -> &mut T {
+        let idx = self.cell_index(x, y);
+        &mut self.data[idx]
+    }
+
+    pub fn get_mut_c<C>(&mut self, coord: C) -> &mut T
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.get_mut(c.x, c.y)
+    }
+
+    pub fn try_get_ref(&self, x: i64, y: i64) -> Option<&T> {
+        self.try_cell_index(x, y).map(|idx| &self.data[idx])
+    }
+
+    pub fn try_get_ref_c<C>(&self, coord: C) -> Option<&T>
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.try_get_ref(c.x, c.y)
+    }
+
+    pub fn try_get_mut(&mut self, x: i64, y: i64) -> Option<&mut T> {
+        self.try_cell_index(x, y).map(|idx| &mut self.data[idx])
+    }
+
+    pub fn try_get_mut_c<C>(&mut self, coord: C) -> Option<&mut T>
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.try_get_mut(c.x, c.y)
+    }
+
+    // Hands the value back if the coordinate is out of bounds
+    pub fn try_set(&mut self, x: i64, y: i64, val: T) -> Result<(), T> {
+        match self.try_get_mut(x, y) {
+            Some(cell) => {
+                *cell = val;
+                Ok(())
+            }
+            None => Err(val),
+        }
+    }
+
+    pub fn try_set_c<C>(&mut self, coord: C, val: T) -> Result<(), T>
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.try_set(c.x, c.y, val)
+    }
+
+    pub fn contains_coord<C>(&self, coord: C) -> bool
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.x_bounds().contains(&c.x) && self.y_bounds().contains(&c.y)
+    }
+
+    // Maps a coordinate into the grid's bounds as if the grid were a torus
+    pub fn wrap_coord<C>(&self, coord: C) -> Coord2D
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        Coord2D::new(
+            (c.x - self.min_x).rem_euclid(self.x_size as i64) + self.min_x,
+            (c.y - self.min_y).rem_euclid(self.y_size as i64) + self.min_y,
+        )
+    }
+
+    // The cells at the given offsets from coord, with their values. Offsets
+    // landing outside the grid are dropped, or wrapped around if `wrap` is set.
+    pub fn neighbors_with<'a, C>(
+        &'a self,
+        coord: C,
+        offsets: &'a [Coord2D],
+        wrap: bool,
+    ) -> impl Iterator<Item = (Coord2D, &'a T)> + 'a
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        offsets.iter().filter_map(move |o| {
+            let n = if wrap {
+                self.wrap_coord(c + *o)
+            } else {
+                c + *o
+            };
+            self.try_get_ref_c(n).map(|v| (n, v))
+        })
+    }
+
+    pub fn neighbors4<C>(&self, coord: C) -> impl Iterator<Item = (Coord2D, &T)> + '_
+    where
+        C: Into<Coord2D>,
+    {
+        self.neighbors_with(coord, &NEIGHBORS4, false)
+    }
+
+    pub fn neighbors8<C>(&self, coord: C) -> impl Iterator<Item = (Coord2D, &T)> + '_
+    where
+        C: Into<Coord2D>,
+    {
+        self.neighbors_with(coord, &NEIGHBORS8, false)
+    }
+
+    pub fn neighbors4_wrap<C>(&self, coord: C) -> impl Iterator<Item = (Coord2D, &T)> + '_
+    where
+        C: Into<Coord2D>,
+    {
+        self.neighbors_with(coord, &NEIGHBORS4, true)
+    }
+
+    pub fn neighbors8_wrap<C>(&self, coord: C) -> impl Iterator<Item = (Coord2D, &T)> + '_
+    where
+        C: Into<Coord2D>,
+    {
+        self.neighbors_with(coord, &NEIGHBORS8, true)
+    }
+
+    pub fn row_slice(&self, y: i64) -> &[T] {
+        let start = self.cell_index(self.min_x, y);
+        &self.data[start..start + self.x_size]
+    }
+
+    pub fn row_slice_mut(&mut self, y: i64) -> &mut [T] {
+        let start = self.cell_index(self.min_x, y);
+        &mut self.data[start..start + self.x_size]
+    }
+
+    pub fn row_slices(&self) -> impl Iterator<Item = &[T]> + '_ {
+        self.data.chunks(self.x_size)
+    }
+
+    pub fn col_view(&self, x: i64) -> StepBy<Iter<'_, T>> {
+        let start = self.cell_index(x, self.min_y);
+        self.data[start..].iter().step_by(self.x_size)
+    }
+
+    pub fn col_views(&self) -> impl Iterator<Item = StepBy<Iter<'_, T>>> + '_ {
+        self.x_bounds().map(|x| self.col_view(x))
+    }
+
+    // The cells from `from` (not included) stepping by `step` until leaving
+    // the grid. `step` can be a CDir or any non-zero Coord2D offset.
+    pub fn ray_ref<C, S>(&self, from: C, step: S) -> Ray<'_, T>
+    where
+        C: Into<Coord2D>,
+        S: Into<Coord2D>,
+    {
+        let step: Coord2D = step.into();
+        assert!(step != Coord2D::new(0, 0), "ray step must be non-zero");
+        Ray {
+            grid: self,
+            pos: from.into() + step,
+            step,
+        }
+    }
+
+    // Every diagonal running down and to the right, starting with the one
+    // through the bottom left corner
+    pub fn diagonals(&self) -> impl Iterator<Item = Ray<'_, T>> + '_ {
+        let xb = self.x_bounds();
+        let yb = self.y_bounds();
+        let starts = yb
+            .clone()
+            .rev()
+            .map(move |y| Coord2D::new(xb.start, y))
+            .chain(
+                self.x_bounds()
+                    .skip(1)
+                    .map(move |x| Coord2D::new(x, yb.start)),
+            );
+        starts.map(|pos| Ray {
+            grid: self,
+            pos,
+            step: Coord2D::new(1, 1),
+        })
+    }
+
+    // Every diagonal running down and to the left, starting with the one
+    // through the top left corner
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = Ray<'_, T>> + '_ {
+        let xb = self.x_bounds();
+        let yb = self.y_bounds();
+        let starts = xb.clone().map(move |x| Coord2D::new(x, yb.start)).chain(
+            self.y_bounds()
+                .skip(1)
+                .map(move |y| Coord2D::new(xb.end - 1, y)),
+        );
+        starts.map(|pos| Ray {
+            grid: self,
+            pos,
+            step: Coord2D::new(-1, 1),
+        })
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, val: T) {
+        *self.get_mut(x, y) = val;
+    }
+
+    pub fn set_c<C>(&mut self, coord: C, val: T)
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.set(c.x, c.y, val);
+    }
+
+    pub fn set_xform(&mut self, x: i64, y: i64, val: T, xform: GridTransform) {
+        let (x, y) = self.apply_transform(x, y, xform);
+        self.set(x, y, val);
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.data.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.data.iter_mut()
+    }
+
+    pub fn data(&self) -> &Vec<T> {
+        &self.data
+    }
+
+    // A grid with the same bounds and padding, holding f of each cell
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            min_x: self.min_x,
+            min_y: self.min_y,
+            x_size: self.x_size,
+            y_size: self.y_size,
+            data: self.data.iter().map(f).collect(),
+            padding: self.padding,
+        }
+    }
+
+    // One line per row, with the formatter also given each cell's coordinates
+    pub fn format_with<F>(&self, mut formatter: F) -> String
+    where
+        F: FnMut(&T, i64, i64) -> String,
+    {
+        let mut s = String::with_capacity(self.y_size * (self.x_size + 1));
+        for y in self.y_bounds() {
+            for x in self.x_bounds() {
+                s.push_str(&formatter(self.get_ref(x, y), x, y));
+            }
+            s.push('\n');
+        }
+        s
+    }
+
+    pub fn x_bounds(&self) -> Range<i64> {
+        self.min_x..self.min_x + self.x_size as i64
+    }
+
+    pub fn y_bounds(&self) -> Range<i64> {
+        self.min_y..self.min_y + self.y_size as i64
+    }
+
+    // bounds not including padding (i.e., original size from input).
+    // Padding comes from from_input and pad. Operations that leave the size
+    // alone (translate, flips, rotations) keep it; ones that change the size
+    // any other way (resize, crop_to, extract, insert_*/remove_*) drop it, so
+    // all of their result counts as original.
+    pub fn x_bounds_orig(&self) -> Range<i64> {
+        self.min_x + self.padding..self.min_x + self.x_size as i64 - self.padding
+    }
+
+    pub fn y_bounds_orig(&self) -> Range<i64> {
+        self.min_y + self.padding..self.min_y + self.y_size as i64 - self.padding
+    }
+
+    pub fn x_bounds_xform(&self, xform: GridTransform) -> Range<i64> {
+        match xform {
+            GridTransform::Rot90
+            | GridTransform::Rot270
+            | GridTransform::Rot90HFlip
+            | GridTransform::Rot270HFlip => self.y_bounds(),
+            GridTransform::Identity
+            | GridTransform::Rot180
+            | GridTransform::HFlip
+            | GridTransform::VFlip => self.x_bounds(),
+        }
+    }
+
+    pub fn y_bounds_xform(&self, xform: GridTransform) -> Range<i64> {
+        match xform {
+            GridTransform::Rot90
+            | GridTransform::Rot270
+            | GridTransform::Rot90HFlip
+            | GridTransform::Rot270HFlip => self.x_bounds(),
+            GridTransform::Identity
+            | GridTransform::Rot180
+            | GridTransform::HFlip
+            | GridTransform::VFlip => self.y_bounds(),
+        }
+    }
+
+    fn apply_transform(&self, x: i64, y: i64, xform: GridTransform) -> (i64, i64) {
+        let (x, y) = xform.source_offset(
+            x - self.min_x,
+            y - self.min_y,
+            self.x_size as i64,
+            self.y_size as i64,
+        );
+        (x + self.min_x, y + self.min_y)
+    }
+
+    pub fn h_flip_inplace(&mut self) {
+        for row in 0..self.y_size {
+            let rowidx = row * self.x_size;
+            for col in 0..self.x_size / 2 {
+                self.data
+                    .swap(rowidx + col, rowidx + (self.x_size - 1 - col))
+            }
+        }
+    }
+
+    pub fn v_flip_inplace(&mut self) {
+        for row in 0..self.y_size / 2 {
+            let rowidx = row * self.x_size;
+            let row2idx = (self.y_size - 1 - row) * self.x_size;
+            for col in 0..self.x_size {
+                self.data.swap(rowidx + col, row2idx + col);
+            }
+        }
+    }
+
+    pub fn rot180_inplace(&mut self) {
+        for row in 0..self.y_size.div_ceil(2) {
+            let rowidx = row * self.x_size;
+            let row2idx = (self.y_size - 1 - row) * self.x_size;
+            let w = if rowidx == row2idx {
+                self.x_size / 2
+            } else {
+                self.x_size
+            };
+            for col in 0..w {
+                let a = rowidx + col;
+                let b = row2idx + (self.x_size - 1 - col);
+                if a != b {
+                    self.data.swap(a, b);
+                }
+            }
+        }
+    }
+
+    // Moves the grid by (dx, dy); cell values stay put, their coordinates
+    // change
+    pub fn translate(&mut self, dx: i64, dy: i64) {
+        self.min_x += dx;
+        self.min_y += dy;
+    }
+
+    // Removes row y, moving the rows below it up one
+    pub fn remove_row(&mut self, y: i64) -> Vec<T> {
+        assert!(self.y_size > 1);
+        let start = self.cell_index(self.min_x, y);
+        self.y_size -= 1;
+        self.padding = 0;
+        self.data.drain(start..start + self.x_size).collect()
+    }
+
+    // Removes column x, moving the columns right of it left one
+    pub fn remove_col(&mut self, x: i64) -> Vec<T> {
+        assert!(self.x_size > 1);
+        let ux = self.cell_index(x, self.min_y) - self.cell_index(self.min_x, self.min_y);
+        let mut removed = Vec::with_capacity(self.y_size);
+        let old = std::mem::take(&mut self.data);
+        self.data.reserve(old.len() - self.y_size);
+        for (i, v) in old.into_iter().enumerate() {
+            if i % self.x_size == ux {
+                removed.push(v);
+            } else {
+                self.data.push(v);
+            }
+        }
+        self.x_size -= 1;
+        self.padding = 0;
+        removed
+    }
+
+    // Swaps across the main diagonal; the grid must be square
+    fn transpose_inplace(&mut self) {
+        assert_eq!(self.x_size, self.y_size, "grid must be square");
+        let n = self.x_size;
+        for row in 0..n {
+            for col in row + 1..n {
+                self.data.swap(row * n + col, col * n + row);
+            }
+        }
+        std::mem::swap(&mut self.min_x, &mut self.min_y);
+    }
+
+    pub fn rot90_inplace(&mut self) {
+        self.transpose_inplace();
+        self.h_flip_inplace();
+    }
+
+    pub fn rot270_inplace(&mut self) {
+        self.transpose_inplace();
+        self.v_flip_inplace();
+    }
+
+    // Same as transformed(xform), but without a copy. Rotations by 90 or 270
+    // degrees only work on square grids.
+    pub fn transform_inplace(&mut self, xform: GridTransform) {
+        match xform {
+            GridTransform::Identity => {}
+            GridTransform::Rot90 => self.rot90_inplace(),
+            GridTransform::Rot180 => self.rot180_inplace(),
+            GridTransform::Rot270 => self.rot270_inplace(),
+            GridTransform::HFlip => self.h_flip_inplace(),
+            GridTransform::VFlip => self.v_flip_inplace(),
+            GridTransform::Rot90HFlip => self.transpose_inplace(),
+            GridTransform::Rot270HFlip => {
+                self.transpose_inplace();
+                self.rot180_inplace();
+            }
+        }
+    }
+
+    pub fn roll_row(&mut self, y: i64, n: i64) {
+        let go_left = n < 0;
+        let n = n.abs() % self.x_size as i64;
+        if n == 0 {
+            return;
+        }
+        let uy: usize = (y - self.min_y) as usize;
+        let idx = uy * self.x_size;
+        let row_slice = &mut self.data[idx..idx + self.x_size];
+        if go_left {
+            row_slice.rotate_left(n as usize);
+        } else {
+            row_slice.rotate_right(n as usize);
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
     pub fn new(min_x: i64, min_y: i64, max_x: i64, max_y: i64, initial_val: T) -> Self {
         let x_size = (max_x - min_x + 1) as usize;
         let y_size = (max_y - min_y + 1) as usize;
@@ -104,12 +612,7 @@ impl<T: Copy> Grid<T> {
     }
 
     pub fn get(&self, x: i64, y: i64) -> T {
-        assert!(x >= self.min_x && x < self.min_x + self.x_size as i64);
-        assert!(y >= self.min_y && y < self.min_y + self.y_size as i64);
-        let ux: usize = (x - self.min_x) as usize;
-        let uy: usize = (y - self.min_y) as usize;
-        let idx = uy * self.x_size + ux;
-        self.data[idx]
+        self.get_ref(x, y).clone()
     }
 
     pub fn get_c<C>(&self, coord: C) -> T
@@ -120,138 +623,38 @@ impl<T: Copy> Grid<T> {
         self.get(c.x, c.y)
     }
 
-    pub fn get_xform(&self, x: i64, y: i64, xform: GridTransform) -> T {
-        let (x, y) = self.apply_transform(x, y, xform);
-        self.get(x, y)
-    }
-
-    pub fn get_or_default(&self, x: i64, y: i64, default: T) -> T {
-        if x >= self.min_x
-            && x < self.min_x + self.x_size as i64
-            && y >= self.min_y
-            && y < self.min_y + self.y_size as i64
-        {
-            let ux: usize = (x - self.min_x) as usize;
-            let uy: usize = (y - self.min_y) as usize;
-            let idx = uy * self.x_size + ux;
-            self.data[idx]
-        } else {
-            default
-        }
-    }
-
-    pub fn get_or_default_xform(&self, x: i64, y: i64, default: T, xform: GridTransform) -> T {
-        let (x, y) = self.apply_transform(x, y, xform);
-        self.get_or_default(x, y, default)
-    }
-
-    pub fn contains_coord<C>(&self, coord: C) -> bool
-    where
-        C: Into<Coord2D>,
-    {
-        let c: Coord2D = coord.into();
-        self.x_bounds().contains(&c.x) && self.y_bounds().contains(&c.y)
-    }
-
-    pub fn set(&mut self, x: i64, y: i64, val: T) {
-        assert!(x >= self.min_x && x < self.min_x + self.x_size as i64);
-        assert!(y >= self.min_y && y < self.min_y + self.y_size as i64);
-        let ux: usize = (x - self.min_x) as usize;
-        let uy: usize = (y - self.min_y) as usize;
-        let idx = uy * self.x_size + ux;
-        self.data[idx] = val;
-    }
-
-    pub fn set_c<C>(&mut self, coord: C, val: T)
-    where
-        C: Into<Coord2D>,
-    {
-        let c: Coord2D = coord.into();
-        self.set(c.x, c.y, val);
-    }
-
-    pub fn set_xform(&mut self, x: i64, y: i64, val: T, xform: GridTransform) {
-        let (x, y) = self.apply_transform(x, y, xform);
-        self.set(x, y, val);
-    }
-
-    pub fn iter(&self) -> Iter<T> {
-        self.data.iter()
-    }
-
-    pub fn iter_mut(&mut self) -> IterMut<T> {
-        self.data.iter_mut()
-    }
-
-    pub fn data(&self) -> &Vec<T> {
-        &self.data
-    }
-
-    pub fn iter_with_coord(&self) -> impl Iterator<Item = (T, i64, i64)> + '_ {
-        self.data.iter().enumerate().map(|(idx, val)| {
-            let x = (idx % self.x_size) as i64 + self.min_x;
-            let y = (idx / self.x_size) as i64 + self.min_y;
-            (*val, x, y)
-        })
-    }
-
-    pub fn x_bounds(&self) -> Range<i64> {
-        self.min_x..self.min_x + self.x_size as i64
-    }
-
-    pub fn y_bounds(&self) -> Range<i64> {
-        self.min_y..self.min_y + self.y_size as i64
+    pub fn get_xform(&self, x: i64, y: i64, xform: GridTransform) -> T {
+        let (x, y) = self.apply_transform(x, y, xform);
+        self.get(x, y)
     }
 
-    // bounds not including padding (i.e., original size from input)
-    pub fn x_bounds_orig(&self) -> Range<i64> {
-        self.min_x + self.padding..self.min_x + self.x_size as i64 - self.padding
+    pub fn try_get(&self, x: i64, y: i64) -> Option<T> {
+        self.try_get_ref(x, y).cloned()
     }
-    pub fn y_bounds_orig(&self) -> Range<i64> {
-        self.min_y + self.padding..self.min_y + self.y_size as i64 - self.padding
+
+    pub fn try_get_c<C>(&self, coord: C) -> Option<T>
+    where
+        C: Into<Coord2D>,
+    {
+        let c: Coord2D = coord.into();
+        self.try_get(c.x, c.y)
     }
 
-    pub fn x_bounds_xform(&self, xform: GridTransform) -> Range<i64> {
-        match xform {
-            GridTransform::Rot90
-            | GridTransform::Rot270
-            | GridTransform::Rot90HFlip
-            | GridTransform::Rot270HFlip => self.y_bounds(),
-            GridTransform::Identity
-            | GridTransform::Rot180
-            | GridTransform::HFlip
-            | GridTransform::VFlip => self.x_bounds(),
-        }
+    pub fn get_or_default(&self, x: i64, y: i64, default: T) -> T {
+        self.try_get(x, y).unwrap_or(default)
     }
-    pub fn y_bounds_xform(&self, xform: GridTransform) -> Range<i64> {
-        match xform {
-            GridTransform::Rot90
-            | GridTransform::Rot270
-            | GridTransform::Rot90HFlip
-            | GridTransform::Rot270HFlip => self.x_bounds(),
-            GridTransform::Identity
-            | GridTransform::Rot180
-            | GridTransform::HFlip
-            | GridTransform::VFlip => self.y_bounds(),
-        }
+
+    pub fn get_or_default_xform(&self, x: i64, y: i64, default: T, xform: GridTransform) -> T {
+        let (x, y) = self.apply_transform(x, y, xform);
+        self.get_or_default(x, y, default)
     }
 
-    fn apply_transform(&self, x: i64, y: i64, xform: GridTransform) -> (i64, i64) {
-        let xsize = self.x_bounds().end - self.x_bounds().start;
-        let ysize = self.y_bounds().end - self.y_bounds().start;
-        let x = x - self.min_x;
-        let y = y - self.min_y;
-        let (x, y) = match xform {
-            GridTransform::Identity => (x, y),
-            GridTransform::Rot90 => (y, ysize - 1 - x),
-            GridTransform::Rot180 => (xsize - 1 - x, ysize - 1 - y),
-            GridTransform::Rot270 => (xsize - 1 - y, x),
-            GridTransform::HFlip => (xsize - 1 - x, y),
-            GridTransform::Rot90HFlip => (y, x),
-            GridTransform::VFlip => (x, ysize - 1 - y),
-            GridTransform::Rot270HFlip => (xsize - 1 - y, ysize - 1 - x),
-        };
-        (x + self.min_x, y + self.min_y)
+    pub fn iter_with_coord(&self) -> impl Iterator<Item = (T, i64, i64)> + '_ {
+        self.data.iter().enumerate().map(|(idx, val)| {
+            let x = (idx % self.x_size) as i64 + self.min_x;
+            let y = (idx / self.x_size) as i64 + self.min_y;
+            (val.clone(), x, y)
+        })
     }
 
     pub fn dump_to_file<F>(&self, file: &mut dyn Write, formatter: F)
@@ -308,14 +711,7 @@ impl<T: Copy> Grid<T> {
     where
         F: Fn(T) -> String,
     {
-        let mut s = String::with_capacity(self.y_size * (self.x_size + 1));
-        for y in self.min_y..self.min_y + self.y_size as i64 {
-            for x in self.min_x..self.min_x + self.x_size as i64 {
-                s.push_str(&formatter(self.get(x, y)));
-            }
-            s.push('\n');
-        }
-        s
+        self.format_with(|v, _, _| formatter(v.clone()))
     }
 
     pub fn find<F>(&self, predicate: F) -> Option<(i64, i64)>
@@ -343,13 +739,23 @@ impl<T: Copy> Grid<T> {
         }
     }
 
+    // Like ray_ref, but with cloned values
+    pub fn ray<C, S>(&self, from: C, step: S) -> impl Iterator<Item = (Coord2D, T)> + '_
+    where
+        C: Into<Coord2D>,
+        S: Into<Coord2D>,
+    {
+        self.ray_ref(from, step).map(|(c, v)| (c, v.clone()))
+    }
+
     pub fn rows(&self) -> impl Iterator<Item = Vec<T>> + '_ {
         (0..self.y_size).map(|y| Vec::from(&self.data[y * self.x_size..(y + 1) * self.x_size]))
     }
 
     pub fn cols(&self) -> impl Iterator<Item = Vec<T>> + '_ {
-        (0..self.x_size)
-            .map(|x| Vec::from_iter((0..self.y_size).map(|y| self.data[x + y * self.x_size])))
+        (0..self.x_size).map(|x| {
+            Vec::from_iter((0..self.y_size).map(|y| self.data[x + y * self.x_size].clone()))
+        })
     }
 
     pub fn extract(&self, x: i64, y: i64, wid: i64, hei: i64) -> Self {
@@ -386,7 +792,7 @@ impl<T: Copy> Grid<T> {
             })
     }
 
-    pub fn from_tiles(tiles: &Vec<Self>, n_tiles_width: usize, n_tiles_height: usize) -> Self {
+    pub fn from_tiles(tiles: &[Self], n_tiles_width: usize, n_tiles_height: usize) -> Self {
         let tilewidth = tiles[0].x_size;
         let tileheight = tiles[0].y_size;
         assert!(tiles
@@ -419,22 +825,12 @@ impl<T: Copy> Grid<T> {
         }
     }
 
-    pub fn h_flip_inplace(&mut self) {
-        for row in 0..self.y_size {
-            let rowidx = row * self.x_size;
-            for col in 0..self.x_size / 2 {
-                self.data
-                    .swap(rowidx + col, rowidx + (self.x_size - 1 - col))
-            }
-        }
-    }
-
     pub fn h_flip(&self) -> Self {
         let mut data = Vec::with_capacity(self.x_size * self.y_size);
         for row in 0..self.y_size {
             let rowidx = row * self.x_size;
             for col in 0..self.x_size {
-                data.push(self.data[rowidx + (self.x_size - 1 - col)]);
+                data.push(self.data[rowidx + (self.x_size - 1 - col)].clone());
             }
         }
         Self {
@@ -447,22 +843,12 @@ impl<T: Copy> Grid<T> {
         }
     }
 
-    pub fn v_flip_inplace(&mut self) {
-        for row in 0..self.y_size / 2 {
-            let rowidx = row * self.x_size;
-            let row2idx = (self.y_size - 1 - row) * self.x_size;
-            for col in 0..self.x_size {
-                self.data.swap(rowidx + col, row2idx + col);
-            }
-        }
-    }
-
     pub fn v_flip(&self) -> Self {
         let mut data = Vec::with_capacity(self.x_size * self.y_size);
         for row in 0..self.y_size {
             let rowidx = (self.y_size - 1 - row) * self.x_size;
             for col in 0..self.x_size {
-                data.push(self.data[rowidx + col]);
+                data.push(self.data[rowidx + col].clone());
             }
         }
         Self {
@@ -475,31 +861,12 @@ impl<T: Copy> Grid<T> {
         }
     }
 
-    pub fn rot180_inplace(&mut self) {
-        for row in 0..(self.y_size + 1) / 2 {
-            let rowidx = row * self.x_size;
-            let row2idx = (self.y_size - 1 - row) * self.x_size;
-            let w = if rowidx == row2idx {
-                self.x_size / 2
-            } else {
-                self.x_size
-            };
-            for col in 0..w {
-                let a = rowidx + col;
-                let b = row2idx + (self.x_size - 1 - col);
-                if a != b {
-                    self.data.swap(a, b);
-                }
-            }
-        }
-    }
-
     pub fn rot180(&self) -> Self {
         let mut data = Vec::with_capacity(self.x_size * self.y_size);
         for row in 0..self.y_size {
             let rowidx = (self.y_size - 1 - row) * self.x_size;
             for col in 0..self.x_size {
-                data.push(self.data[rowidx + (self.x_size - 1 - col)]);
+                data.push(self.data[rowidx + (self.x_size - 1 - col)].clone());
             }
         }
         Self {
@@ -516,7 +883,7 @@ impl<T: Copy> Grid<T> {
         let mut data = Vec::with_capacity(self.x_size * self.y_size);
         for col in 0..self.x_size {
             for row in 0..self.y_size {
-                data.push(self.data[(self.y_size - 1 - row) * self.x_size + col]);
+                data.push(self.data[(self.y_size - 1 - row) * self.x_size + col].clone());
             }
         }
 
@@ -530,19 +897,106 @@ impl<T: Copy> Grid<T> {
         }
     }
 
-    pub fn roll_row(&mut self, y: i64, n: i64) {
-        let go_left = n < 0;
-        let n = n.abs() % self.x_size as i64;
-        if n == 0 {
-            return;
+    // Adds n cells of val around every side. They count as padding, on top of
+    // any the grid already had.
+    pub fn pad(&self, n: i64, val: T) -> Self {
+        let mut inst = self.resize(
+            self.min_x - n,
+            self.min_y - n,
+            self.min_x + self.x_size as i64 - 1 + n,
+            self.min_y + self.y_size as i64 - 1 + n,
+            val,
+        );
+        inst.padding = self.padding + n;
+        inst
+    }
+
+    // A grid with the given bounds, where cells that were already in the
+    // grid keep their values and coordinates, and new cells are set to val
+    pub fn resize(&self, min_x: i64, min_y: i64, max_x: i64, max_y: i64, val: T) -> Self {
+        Grid::new_with(min_x, min_y, max_x, max_y, |x, y| {
+            self.try_get_ref(x, y).unwrap_or(&val).clone()
+        })
+    }
+
+    // Shrinks the grid to the bounding box of the cells matching predicate,
+    // or None if there aren't any. Coordinates are unchanged.
+    pub fn crop_to<F>(&self, predicate: F) -> Option<Self>
+    where
+        F: Fn(Coord2D, &T) -> bool,
+    {
+        let (min, max) = self
+            .y_bounds()
+            .flat_map(|y| self.x_bounds().map(move |x| Coord2D::new(x, y)))
+            .filter(|c| predicate(*c, self.get_ref_c(*c)))
+            .fold(None, |acc: Option<(Coord2D, Coord2D)>, c| match acc {
+                None => Some((c, c)),
+                Some((min, max)) => Some((
+                    Coord2D::new(min.x.min(c.x), min.y.min(c.y)),
+                    Coord2D::new(max.x.max(c.x), max.y.max(c.y)),
+                )),
+            })?;
+        Some(Grid::new_with(min.x, min.y, max.x, max.y, |x, y| {
+            self.get(x, y)
+        }))
+    }
+
+    // Inserts a row of val before row y (or after the last row if y is just
+    // past it), moving the rows below down one
+    pub fn insert_row(&mut self, y: i64, val: T) {
+        assert!(y >= self.min_y && y <= self.min_y + self.y_size as i64);
+        let start = (y - self.min_y) as usize * self.x_size;
+        self.data
+            .splice(start..start, std::iter::repeat_n(val, self.x_size));
+        self.y_size += 1;
+        self.padding = 0;
+    }
+
+    // Inserts a column of val before column x (or after the last one if x is
+    // just past it), moving the columns to the right over one
+    pub fn insert_col(&mut self, x: i64, val: T) {
+        assert!(x >= self.min_x && x <= self.min_x + self.x_size as i64);
+        let ux = (x - self.min_x) as usize;
+        let old = std::mem::take(&mut self.data);
+        self.data.reserve(old.len() + self.y_size);
+        for (i, v) in old.into_iter().enumerate() {
+            if i % self.x_size == ux {
+                self.data.push(val.clone());
+            }
+            self.data.push(v);
+            if ux == self.x_size && i % self.x_size == self.x_size - 1 {
+                self.data.push(val.clone());
+            }
         }
-        let uy: usize = (y - self.min_y) as usize;
-        let idx = uy * self.x_size;
-        let row_slice = &mut self.data[idx..idx + self.x_size];
-        if go_left {
-            row_slice.rotate_left(n as usize);
+        self.x_size += 1;
+        self.padding = 0;
+    }
+
+    pub fn rot270(&self) -> Self {
+        self.transformed(GridTransform::Rot270)
+    }
+
+    // A copy of the grid with cell (x, y) holding get_xform(x, y, xform)
+    pub fn transformed(&self, xform: GridTransform) -> Self {
+        let (x_size, y_size, min_x, min_y) = if xform.swaps_axes() {
+            (self.y_size, self.x_size, self.min_y, self.min_x)
         } else {
-            row_slice.rotate_right(n as usize);
+            (self.x_size, self.y_size, self.min_x, self.min_y)
+        };
+        let mut data = Vec::with_capacity(self.data.len());
+        for y in 0..y_size as i64 {
+            for x in 0..x_size as i64 {
+                let (sx, sy) = xform.source_offset(x, y, self.x_size as i64, self.y_size as i64);
+                data.push(self.data[sy as usize * self.x_size + sx as usize].clone());
+            }
+        }
+        Self {
+            min_x,
+            min_y,
+            x_size,
+            y_size,
+            data,
+            padding: self.padding,
         }
     }
 
@@ -553,13 +1007,104 @@ impl<T: Copy> Grid<T> {
             return;
         }
         let ux: usize = (x - self.min_x) as usize;
-        let mut col = Vec::from_iter((0..self.y_size).map(|row| self.data[row * self.x_size + ux]));
+        let mut col =
+            Vec::from_iter((0..self.y_size).map(|row| self.data[row * self.x_size + ux].clone()));
         if go_up {
             col.rotate_left(n as usize);
         } else {
             col.rotate_right(n as usize);
         }
-        (0..self.y_size).for_each(|row| self.data[row * self.x_size + ux] = col[row]);
+        col.into_iter()
+            .enumerate()
+            .for_each(|(row, val)| self.data[row * self.x_size + ux] = val);
+    }
+}
+
+// A fingerprint is the XOR of a hash of every cell and its coordinates, so
+// it can be kept up to date as cells change without rehashing the grid.
+// Equal grids have equal fingerprints; the converse only holds with high
+// probability.
+impl<T: Hash> Grid<T> {
+    fn cell_hash(x: i64, y: i64, val: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (x, y, val).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn fingerprint(&self) -> u64 {
+        self.y_bounds()
+            .cartesian_product(self.x_bounds())
+            .zip(self.data.iter())
+            .fold(0, |fp, ((y, x), v)| fp ^ Self::cell_hash(x, y, v))
+    }
+
+    // set(), updating a fingerprint from fingerprint() to match
+    pub fn set_fingerprinted(&mut self, x: i64, y: i64, val: T, fingerprint: &mut u64) {
+        let cell = self.get_mut(x, y);
+        *fingerprint ^= Self::cell_hash(x, y, cell) ^ Self::cell_hash(x, y, &val);
+        *cell = val;
+    }
+}
+
+// Grids are equal if they have the same bounds and cells. Padding isn't
+// compared, so it doesn't matter how a grid came by its bounds.
+impl<T: PartialEq> PartialEq for Grid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.min_x, self.min_y, self.x_size, self.y_size)
+            == (other.min_x, other.min_y, other.x_size, other.y_size)
+            && self.data == other.data
+    }
+}
+
+impl<T: Eq> Eq for Grid<T> {}
+
+impl<T: Hash> Hash for Grid<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.min_x, self.min_y, self.x_size, self.y_size).hash(state);
+        self.data.hash(state);
+    }
+}
+
+impl<T> Index<Coord2D> for Grid<T> {
+    type Output = T;
+    fn index(&self, c: Coord2D) -> &T {
+        self.get_ref(c.x, c.y)
+    }
+}
+
+impl<T> IndexMut<Coord2D> for Grid<T> {
+    fn index_mut(&mut self, c: Coord2D) -> &mut T {
+        self.get_mut(c.x, c.y)
+    }
+}
+
+impl<T> Index<(i64, i64)> for Grid<T> {
+    type Output = T;
+    fn index(&self, (x, y): (i64, i64)) -> &T {
+        self.get_ref(x, y)
+    }
+}
+
+impl<T> IndexMut<(i64, i64)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (i64, i64)) -> &mut T {
+        self.get_mut(x, y)
+    }
+}
+
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    pos: Coord2D,
+    step: Coord2D,
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = (Coord2D, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let c = self.pos;
+        let val = self.grid.try_get_ref_c(c)?;
+        self.pos += self.step;
+        Some((c, val))
     }
 }
 
@@ -575,9 +1120,77 @@ pub enum GridTransform {
     Rot270HFlip,
 }
 
+impl GridTransform {
+    pub fn all() -> [GridTransform; 8] {
+        [
+            GridTransform::Identity,
+            GridTransform::Rot90,
+            GridTransform::Rot180,
+            GridTransform::Rot270,
+            GridTransform::HFlip,
+            GridTransform::VFlip,
+            GridTransform::Rot90HFlip,
+            GridTransform::Rot270HFlip,
+        ]
+    }
+
+    // Where a cell of the transformed grid comes from, as a matrix applied
+    // to its coordinates (ignoring the translation back into the grid)
+    fn matrix(&self) -> [[i64; 2]; 2] {
+        match self {
+            GridTransform::Identity => [[1, 0], [0, 1]],
+            GridTransform::Rot90 => [[0, 1], [-1, 0]],
+            GridTransform::Rot180 => [[-1, 0], [0, -1]],
+            GridTransform::Rot270 => [[0, -1], [1, 0]],
+            GridTransform::HFlip => [[-1, 0], [0, 1]],
+            GridTransform::VFlip => [[1, 0], [0, -1]],
+            GridTransform::Rot90HFlip => [[0, 1], [1, 0]],
+            GridTransform::Rot270HFlip => [[0, -1], [-1, 0]],
+        }
+    }
+
+    fn from_matrix(m: [[i64; 2]; 2]) -> Self {
+        *Self::all().iter().find(|t| t.matrix() == m).unwrap()
+    }
+
+    pub fn swaps_axes(&self) -> bool {
+        self.matrix()[0][0] == 0
+    }
+
+    // Offset of the source cell for an offset in the transformed grid, given
+    // the size of the source grid
+    fn source_offset(&self, x: i64, y: i64, xsize: i64, ysize: i64) -> (i64, i64) {
+        let [[a, b], [c, d]] = self.matrix();
+        // a negative row means counting back from the far edge
+        let sx = a * x + b * y + if a + b < 0 { xsize - 1 } else { 0 };
+        let sy = c * x + d * y + if c + d < 0 { ysize - 1 } else { 0 };
+        (sx, sy)
+    }
+
+    // The transform that does self and then other, so that
+    // grid.transformed(a).transformed(b) == grid.transformed(a.compose(b))
+    pub fn compose(&self, other: GridTransform) -> Self {
+        let a = self.matrix();
+        let b = other.matrix();
+        let mut m = [[0; 2]; 2];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, v) in row.iter_mut().enumerate() {
+                *v = a[i][0] * b[0][j] + a[i][1] * b[1][j];
+            }
+        }
+        Self::from_matrix(m)
+    }
+
+    pub fn inverse(&self) -> Self {
+        let [[a, b], [c, d]] = self.matrix();
+        Self::from_matrix([[a, c], [b, d]])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::coords::CDir;
 
     fn fill(grid: &mut Grid<u32>) {
         let mut c = 0u32;
@@ -726,6 +1339,304 @@ mod tests {
                                    20, 21, 22,  3, 24]);
     }
 
+    #[test]
+    fn test_non_copy() {
+        let mut grid: Grid<Vec<i64>> = Grid::new_with(0, 0, 2, 1, |x, y| vec![x, y]);
+        assert_eq!(grid.get_ref(2, 1), &vec![2, 1]);
+        grid.get_mut(1, 0).push(5);
+        grid.set_c((0, 1), vec![]);
+        assert_eq!(grid.get(1, 0), vec![1, 0, 5]);
+        assert!(grid.get_ref_c(Coord2D::new(0, 1)).is_empty());
+
+        let grid: Grid<String> = Grid::new_with(0, 0, 1, 1, |x, y| format!("{x}{y}"));
+        let grid = grid.rot90();
+        assert_eq!(grid.format_str(|s| s + " "), "01 00 \n11 10 \n");
+    }
+
+    #[test]
+    fn test_checked_access() {
+        let mut grid: Grid<u32> = Grid::new(-1, -1, 1, 1, 0);
+        fill(&mut grid);
+        assert_eq!(grid.try_get(1, 1), Some(8));
+        assert_eq!(grid.try_get(2, 1), None);
+        assert_eq!(grid.try_get_c(Coord2D::new(-1, -2)), None);
+        assert_eq!(grid.try_set(0, 0, 40), Ok(()));
+        assert_eq!(grid.try_set_c((0, 2), 41), Err(41));
+        if let Some(v) = grid.try_get_mut(-1, -1) {
+            *v += 10;
+        }
+        assert!(grid.try_get_mut_c((5, 5)).is_none());
+        assert_eq!(grid[(-1, -1)], 10);
+        assert_eq!(grid[Coord2D::new(0, 0)], 40);
+        grid[Coord2D::new(1, 0)] = 50;
+        grid[(1, 1)] += 1;
+        assert_eq!(grid.data, vec![10, 1, 2, 3, 40, 50, 6, 7, 9]);
+    }
+
+    #[test]
+    fn test_dihedral() {
+        let mut grid: Grid<u32> = Grid::new(-2, -2, 2, 3, 0);
+        fill(&mut grid);
+        for t in GridTransform::all() {
+            let grid2 = grid.transformed(t);
+            assert_eq!(grid2.x_bounds(), grid.x_bounds_xform(t));
+            assert_eq!(grid2.y_bounds(), grid.y_bounds_xform(t));
+            for y in grid2.y_bounds() {
+                for x in grid2.x_bounds() {
+                    assert_eq!(grid.get_xform(x, y, t), grid2.get(x, y));
+                }
+            }
+            assert_eq!(grid2.transformed(t.inverse()).data, grid.data);
+            assert_eq!(t.inverse().compose(t), GridTransform::Identity);
+            for u in GridTransform::all() {
+                let composed = grid.transformed(t.compose(u));
+                assert_eq!(grid2.transformed(u).data, composed.data);
+            }
+        }
+        assert_eq!(grid.rot270().data, grid.rot90().rot180().data);
+        assert_eq!(
+            GridTransform::Rot90.compose(GridTransform::HFlip),
+            GridTransform::Rot90HFlip
+        );
+
+        let mut square: Grid<u32> = Grid::new(0, 0, 3, 3, 0);
+        fill(&mut square);
+        for t in GridTransform::all() {
+            let mut grid2 = square.clone();
+            grid2.transform_inplace(t);
+            assert_eq!(grid2.data, square.transformed(t).data);
+        }
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut grid: Grid<u32> = Grid::new(0, 0, 2, 1, 0);
+        fill(&mut grid);
+        let padded = grid.pad(1, 9);
+        assert_eq!(padded.x_bounds(), -1..4);
+        assert_eq!(padded.x_bounds_orig(), 0..3);
+        assert_eq!((padded.get(-1, -1), padded.get(2, 1)), (9, 5));
+        let cropped = padded.crop_to(|_, v| *v != 9).unwrap();
+        assert_eq!(cropped.data, grid.data);
+        assert_eq!(cropped.x_bounds(), 0..3);
+        let cropped = grid.crop_to(|c, v| c.x > 0 && *v % 2 == 0).unwrap();
+        assert_eq!((cropped.x_bounds(), cropped.y_bounds()), (1..3, 0..2));
+        assert!(grid.crop_to(|_, v| *v > 10).is_none());
+
+        let resized = grid.resize(1, -1, 3, 0, 7);
+        assert_eq!(resized.data, vec![7, 7, 7, 1, 2, 7]);
+
+        let mut grid2 = grid.clone();
+        grid2.insert_row(1, 8);
+        grid2.insert_row(3, 9);
+        assert_eq!(grid2.data, vec![0, 1, 2, 8, 8, 8, 3, 4, 5, 9, 9, 9]);
+        assert_eq!(grid2.remove_row(1), vec![8, 8, 8]);
+        grid2.insert_col(0, 6);
+        grid2.insert_col(4, 7);
+        assert_eq!(grid2.y_bounds(), 0..3);
+        assert_eq!(
+            grid2.data,
+            vec![6, 0, 1, 2, 7, 6, 3, 4, 5, 7, 6, 9, 9, 9, 7]
+        );
+        assert_eq!(grid2.remove_col(2), vec![1, 4, 9]);
+        assert_eq!(grid2.x_bounds(), 0..4);
+
+        grid.translate(-5, 10);
+        assert_eq!((grid.x_bounds(), grid.y_bounds()), (-5..-2, 10..12));
+        assert_eq!(grid.get(-4, 11), 4);
+    }
+
+    #[test]
+    fn test_map() {
+        let mut grid: Grid<u32> = Grid::new(0, 0, 2, 1, 0);
+        fill(&mut grid);
+        let odd = grid.pad(1, 0).map(|v| v % 2 == 1);
+        assert_eq!(odd.x_bounds_orig(), 0..3);
+        assert_eq!(
+            odd.format_with(|v, x, y| if x == y {
+                "\\".into()
+            } else {
+                (*v as u8).to_string()
+            }),
+            "\\0000\n0\\100\n01\\10\n000\\0\n"
+        );
+    }
+
+    #[test]
+    fn test_padding() {
+        let mut grid: Grid<u32> = Grid::new(0, 0, 2, 1, 0);
+        fill(&mut grid);
+        let orig = |g: &Grid<u32>| (g.x_bounds_orig(), g.y_bounds_orig());
+        let whole = |g: &Grid<u32>| (g.x_bounds(), g.y_bounds());
+        let padded = grid.pad(1, 9).pad(1, 8);
+        assert_eq!(whole(&padded), (-2..5, -2..4));
+        assert_eq!(orig(&padded), (0..3, 0..2));
+
+        // kept while the size stays the same
+        let mut moved = padded.clone();
+        moved.translate(5, -5);
+        assert_eq!(orig(&moved), (5..8, -5..-3));
+        assert_eq!(orig(&padded.h_flip()), (0..3, 0..2));
+        let turned = padded.rot90();
+        assert_eq!(
+            (
+                turned.x_bounds_orig().count(),
+                turned.y_bounds_orig().count()
+            ),
+            (2, 3)
+        );
+
+        // dropped by anything else that changes the size
+        let resized = padded.resize(-3, -2, 5, 3, 0);
+        assert_eq!(orig(&resized), whole(&resized));
+        let cropped = padded.crop_to(|_, v| *v != 8).unwrap();
+        assert_eq!(orig(&cropped), (-1..4, -1..3));
+        let mut changed = padded.clone();
+        changed.insert_row(0, 7);
+        assert_eq!(orig(&changed), whole(&changed));
+        let mut changed = padded.clone();
+        changed.insert_col(0, 7);
+        assert_eq!(orig(&changed), whole(&changed));
+        let mut changed = padded.clone();
+        changed.remove_row(0);
+        assert_eq!(orig(&changed), whole(&changed));
+        let mut changed = padded.clone();
+        changed.remove_col(0);
+        assert_eq!(orig(&changed), whole(&changed));
+    }
+
+    #[test]
+    fn test_hash() {
+        let mut grid: Grid<u32> = Grid::new(0, 0, 3, 2, 0);
+        fill(&mut grid);
+        let mut fp = grid.fingerprint();
+        let mut seen = std::collections::HashSet::new();
+        seen.insert(grid.clone());
+        let mut copy = grid.clone();
+        copy.set_fingerprinted(1, 1, 99, &mut fp);
+        assert_ne!(copy, grid);
+        assert_eq!(fp, copy.fingerprint());
+        assert_ne!(fp, grid.fingerprint());
+        copy.set_fingerprinted(1, 1, 5, &mut fp);
+        assert_eq!(copy, grid);
+        assert_eq!(fp, grid.fingerprint());
+        assert!(seen.contains(&copy));
+
+        // padding isn't part of the state
+        let padded = grid.pad(1, 0);
+        let resized = grid.resize(-1, -1, 4, 3, 0);
+        assert_eq!(padded, resized);
+        seen.insert(padded);
+        assert!(seen.contains(&resized));
+
+        // rolling a row round comes back after its length
+        let step = |g: &Grid<u32>| {
+            let mut g = g.clone();
+            g.roll_row(1, 1);
+            g
+        };
+        let (_, repeat) =
+            crate::algorithm::find_repeat_by_key(grid.clone(), step, |g| g.fingerprint());
+        assert_eq!((repeat.first, repeat.period), (0, 4));
+        let after = crate::algorithm::state_after(grid.clone(), 1_000_000_001, step);
+        assert_eq!(after, step(&grid));
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero")]
+    fn test_zero_ray() {
+        let grid: Grid<u32> = Grid::new(0, 0, 2, 2, 0);
+        grid.ray((1, 1), (0, 0)).count();
+    }
+
+    #[test]
+    fn test_views() {
+        let mut grid: Grid<u32> = Grid::new(-1, 0, 2, 2, 0);
+        fill(&mut grid);
+        assert_eq!(grid.row_slice(1), &[4, 5, 6, 7]);
+        grid.row_slice_mut(2)[0] = 20;
+        assert_eq!(grid.row_slices().last().unwrap(), &[20, 9, 10, 11]);
+        assert_eq!(
+            grid.col_view(1).copied().collect::<Vec<_>>(),
+            vec![2, 6, 10]
+        );
+        assert_eq!(grid.col_view(0).next_back(), Some(&9));
+        assert_eq!(grid.col_views().count(), 4);
+
+        let ray: Vec<(Coord2D, u32)> = grid.ray((-1, 2), CDir::N).collect();
+        assert_eq!(
+            ray,
+            vec![(Coord2D::new(-1, 1), 4), (Coord2D::new(-1, 0), 0)]
+        );
+        assert_eq!(
+            grid.ray((-1, 0), (2, 1))
+                .map(|(_, v)| v)
+                .collect::<Vec<_>>(),
+            vec![6]
+        );
+        assert_eq!(grid.ray_ref((2, 2), CDir::E).count(), 0);
+        // the first cell greater than 5 looking right from (-1, 1)
+        assert_eq!(
+            grid.ray((-1, 1), CDir::E).find(|(_, v)| *v > 5).unwrap().1,
+            6
+        );
+
+        let diags: Vec<Vec<u32>> = grid
+            .diagonals()
+            .map(|d| d.map(|(_, v)| *v).collect())
+            .collect();
+        assert_eq!(
+            diags,
+            vec![
+                vec![20],
+                vec![4, 9],
+                vec![0, 5, 10],
+                vec![1, 6, 11],
+                vec![2, 7],
+                vec![3]
+            ]
+        );
+        let anti: Vec<Vec<u32>> = grid
+            .anti_diagonals()
+            .map(|d| d.map(|(_, v)| *v).collect())
+            .collect();
+        assert_eq!(
+            anti,
+            vec![
+                vec![0],
+                vec![1, 4],
+                vec![2, 5, 20],
+                vec![3, 6, 9],
+                vec![7, 10],
+                vec![11]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let mut grid: Grid<u32> = Grid::new(-1, -1, 2, 1, 0);
+        fill(&mut grid);
+        let vals =
+            |it: &mut dyn Iterator<Item = (Coord2D, &u32)>| it.map(|(_, v)| *v).collect::<Vec<_>>();
+        assert_eq!(vals(&mut grid.neighbors4((-1, -1))), vec![1, 4]);
+        assert_eq!(vals(&mut grid.neighbors4((0, 0))), vec![1, 4, 6, 9]);
+        assert_eq!(vals(&mut grid.neighbors8((2, 1))), vec![6, 7, 10]);
+        assert_eq!(vals(&mut grid.neighbors4_wrap((-1, -1))), vec![8, 3, 1, 4]);
+        assert_eq!(
+            grid.neighbors8_wrap((2, 1))
+                .map(|(c, _)| c)
+                .collect::<Vec<_>>()[..3],
+            [Coord2D::new(1, 0), Coord2D::new(2, 0), Coord2D::new(-1, 0)]
+        );
+        let knight = [Coord2D::new(1, 2), Coord2D::new(2, 1), Coord2D::new(-2, -1)];
+        assert_eq!(
+            vals(&mut grid.neighbors_with((-1, -1), &knight, false)),
+            vec![9, 6]
+        );
+        assert_eq!(grid.wrap_coord((-2, 5)), Coord2D::new(2, -1));
+    }
+
     #[test]
     fn test_xform() {
         /*
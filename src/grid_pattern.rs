@@ -0,0 +1,161 @@
+use crate::coords::{Coord2D, NEIGHBORS8};
+use crate::grid::{Grid, GridTransform};
+
+// Patterns are grids of Option<T>, with None cells matching anything. A
+// pattern can be read with e.g.
+//   Grid::from_input_map(&lines, None, 0, |c| (c != ' ').then_some(c))
+
+impl<T: Clone> Grid<Option<T>> {
+    // The cells of the grid covered by the non-wildcard cells of the pattern,
+    // when matched at `at` under xform (as returned by find_pattern)
+    pub fn pattern_cells<C>(&self, at: C, xform: GridTransform) -> Vec<Coord2D>
+    where
+        C: Into<Coord2D>,
+    {
+        let at: Coord2D = at.into();
+        pattern_offsets(&self.transformed(xform))
+            .into_iter()
+            .map(|(o, _)| at + o)
+            .collect()
+    }
+}
+
+// Offsets of the non-wildcard cells from the pattern's top left corner
+fn pattern_offsets<T: Clone>(pattern: &Grid<Option<T>>) -> Vec<(Coord2D, T)> {
+    let xb = pattern.x_bounds();
+    let yb = pattern.y_bounds();
+    pattern
+        .iter_with_coord()
+        .filter_map(|(v, x, y)| v.map(|v| (Coord2D::new(x - xb.start, y - yb.start), v)))
+        .collect()
+}
+
+impl<T: Clone + PartialEq> Grid<T> {
+    // Every place the pattern matches under any of the given transforms, as
+    // the top left corner of the transformed pattern. Transforms that give the
+    // same pattern as an earlier one are skipped, so a symmetric pattern
+    // isn't reported more than once at a position.
+    pub fn find_pattern_with(
+        &self,
+        pattern: &Grid<Option<T>>,
+        xforms: &[GridTransform],
+    ) -> Vec<(Coord2D, GridTransform)> {
+        let xb = self.x_bounds();
+        let yb = self.y_bounds();
+        let mut tried: Vec<Vec<(Coord2D, T)>> = Vec::new();
+        let mut found = Vec::new();
+        for xform in xforms {
+            let p = pattern.transformed(*xform);
+            let cells = pattern_offsets(&p);
+            if tried.contains(&cells) {
+                continue;
+            }
+            let pw = p.x_bounds().end - p.x_bounds().start;
+            let ph = p.y_bounds().end - p.y_bounds().start;
+            for y in yb.start..=yb.end - ph {
+                for x in xb.start..=xb.end - pw {
+                    let at = Coord2D::new(x, y);
+                    if cells.iter().all(|(o, v)| self.get_ref_c(at + *o) == v) {
+                        found.push((at, *xform));
+                    }
+                }
+            }
+            tried.push(cells);
+        }
+        found
+    }
+
+    pub fn find_pattern(&self, pattern: &Grid<Option<T>>) -> Vec<(Coord2D, GridTransform)> {
+        self.find_pattern_with(pattern, &GridTransform::all())
+    }
+
+    // Word search: every start cell and direction (one of NEIGHBORS8) along
+    // which the grid reads `word`. A single-element word reads the same in
+    // every direction, so it is only reported once, left to right.
+    pub fn find_word(&self, word: &[T]) -> Vec<(Coord2D, Coord2D)> {
+        let mut found = Vec::new();
+        if word.is_empty() {
+            return found;
+        }
+        let dirs: &[Coord2D] = if word.len() == 1 {
+            &[Coord2D { x: 1, y: 0 }]
+        } else {
+            &NEIGHBORS8
+        };
+        for y in self.y_bounds() {
+            for x in self.x_bounds() {
+                let start = Coord2D::new(x, y);
+                if *self.get_ref_c(start) != word[0] {
+                    continue;
+                }
+                for &dir in dirs {
+                    let matched = word
+                        .iter()
+                        .enumerate()
+                        .skip(1)
+                        .all(|(i, v)| self.try_get_ref_c(start + dir * i as i64) == Some(v));
+                    if matched {
+                        found.push((start, dir));
+                    }
+                }
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::lines;
+
+    fn to_grid(rows: &[&str]) -> Grid<char> {
+        Grid::from_input(&lines(rows), '.', 0)
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let grid = to_grid(&["#....", "##.##", "....#", "....."]);
+        let pattern = Grid::from_input_map(&lines(&["#.", "##"]), None, 0, |c| {
+            (c == '#').then_some('#')
+        });
+        let mut found = grid.find_pattern_with(&pattern, &[GridTransform::Identity]);
+        assert_eq!(found, vec![(Coord2D::new(0, 0), GridTransform::Identity)]);
+        found = grid.find_pattern(&pattern);
+        assert_eq!(
+            found,
+            vec![
+                (Coord2D::new(0, 0), GridTransform::Identity),
+                (Coord2D::new(3, 1), GridTransform::Rot180),
+            ]
+        );
+        assert_eq!(
+            pattern.pattern_cells((3, 1), GridTransform::Rot180),
+            vec![Coord2D::new(3, 1), Coord2D::new(4, 1), Coord2D::new(4, 2)]
+        );
+
+        // a symmetric pattern is found once per position
+        let pattern = Grid::from_input_map(&lines(&["##"]), None, 0, Some);
+        assert_eq!(grid.find_pattern(&pattern).len(), 4);
+    }
+
+    #[test]
+    fn test_find_word() {
+        let grid = to_grid(&["XMAS", "MM.A", "A.A.", "S..S"]);
+        let word: Vec<char> = "XMAS".chars().collect();
+        let found = grid.find_word(&word);
+        assert_eq!(
+            found,
+            vec![
+                (Coord2D::new(0, 0), Coord2D::new(1, 0)),
+                (Coord2D::new(0, 0), Coord2D::new(0, 1)),
+                (Coord2D::new(0, 0), Coord2D::new(1, 1)),
+            ]
+        );
+        assert!(grid.find_word(&[]).is_empty());
+        assert_eq!(
+            grid.find_word(&['X']),
+            vec![(Coord2D::new(0, 0), Coord2D::new(1, 0))]
+        );
+    }
+}
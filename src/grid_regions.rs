@@ -0,0 +1,225 @@
+use crate::coords::Coord2D;
+use crate::grid::Grid;
+use std::collections::VecDeque;
+
+// Label given to cells that aren't part of any region
+pub const NO_REGION: usize = usize::MAX;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Region {
+    pub label: usize,
+    // first cell of the region in reading order
+    pub start: Coord2D,
+    pub area: usize,
+    pub perimeter: usize,
+    // number of straight edge segments, which is also the number of corners
+    pub sides: usize,
+    pub min: Coord2D,
+    pub max: Coord2D,
+    // whether the region reaches the outermost row/column of the grid
+    pub touches_edge: bool,
+    // whether it reaches the edge of the original input, i.e. the bounds
+    // excluding any padding from from_input
+    pub touches_orig_edge: bool,
+}
+
+pub struct Regions {
+    pub labels: Grid<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl Regions {
+    pub fn region_at<C>(&self, coord: C) -> Option<&Region>
+    where
+        C: Into<Coord2D>,
+    {
+        self.labels
+            .try_get_c(coord)
+            .and_then(|label| self.regions.get(label))
+    }
+
+    pub fn cells(&self, label: usize) -> impl Iterator<Item = Coord2D> + '_ {
+        self.labels
+            .iter_with_coord()
+            .filter(move |(l, _, _)| *l == label)
+            .map(|(_, x, y)| Coord2D::new(x, y))
+    }
+}
+
+impl<T> Grid<T> {
+    // All cells 4-connected to start through cells for which `joined`
+    // returns true (called with the values of a cell and its neighbor).
+    pub fn flood_fill<C, F>(&self, start: C, joined: F) -> Vec<Coord2D>
+    where
+        C: Into<Coord2D>,
+        F: Fn(&T, &T) -> bool,
+    {
+        let start: Coord2D = start.into();
+        let xb = self.x_bounds();
+        let yb = self.y_bounds();
+        let mut seen: Grid<bool> = Grid::new(xb.start, yb.start, xb.end - 1, yb.end - 1, false);
+        let mut cells = vec![start];
+        let mut queue = VecDeque::from([start]);
+        seen.set_c(start, true);
+        while let Some(c) = queue.pop_front() {
+            let val = self.get_ref_c(c);
+            for (n, nval) in self.neighbors4(c) {
+                if !seen.get_c(n) && joined(val, nval) {
+                    seen.set_c(n, true);
+                    cells.push(n);
+                    queue.push_back(n);
+                }
+            }
+        }
+        cells
+    }
+
+    // Labels the 4-connected components of the grid, where neighboring cells
+    // are in the same component if `joined` says so. Cells for which
+    // `include` is false are left out (labelled NO_REGION).
+    pub fn regions_ex<I, F>(&self, include: I, joined: F) -> Regions
+    where
+        I: Fn(Coord2D, &T) -> bool,
+        F: Fn(&T, &T) -> bool,
+    {
+        let xb = self.x_bounds();
+        let yb = self.y_bounds();
+        let mut labels: Grid<usize> =
+            Grid::new(xb.start, yb.start, xb.end - 1, yb.end - 1, NO_REGION);
+        let mut starts = Vec::new();
+        for y in yb.clone() {
+            for x in xb.clone() {
+                let c = Coord2D::new(x, y);
+                if labels.get_c(c) != NO_REGION || !include(c, self.get_ref_c(c)) {
+                    continue;
+                }
+                let label = starts.len();
+                starts.push(c);
+                labels.set_c(c, label);
+                let mut queue = VecDeque::from([c]);
+                while let Some(c) = queue.pop_front() {
+                    let val = self.get_ref_c(c);
+                    for (n, nval) in self.neighbors4(c) {
+                        if labels.get_c(n) == NO_REGION && include(n, nval) && joined(val, nval) {
+                            labels.set_c(n, label);
+                            queue.push_back(n);
+                        }
+                    }
+                }
+            }
+        }
+        let regions = starts
+            .into_iter()
+            .enumerate()
+            .map(|(label, start)| Region {
+                label,
+                start,
+                area: 0,
+                perimeter: 0,
+                sides: 0,
+                min: start,
+                max: start,
+                touches_edge: false,
+                touches_orig_edge: false,
+            })
+            .collect();
+        let mut regions = Regions { labels, regions };
+        self.region_stats(&mut regions);
+        regions
+    }
+
+    fn region_stats(&self, regions: &mut Regions) {
+        let labels = &regions.labels;
+        let xb = self.x_bounds();
+        let yb = self.y_bounds();
+        let xo = self.x_bounds_orig();
+        let yo = self.y_bounds_orig();
+        let same = |label: usize, x: i64, y: i64| labels.get_or_default(x, y, NO_REGION) == label;
+        for (label, x, y) in labels.iter_with_coord() {
+            if label == NO_REGION {
+                continue;
+            }
+            let r = &mut regions.regions[label];
+            r.area += 1;
+            r.min = Coord2D::new(r.min.x.min(x), r.min.y.min(y));
+            r.max = Coord2D::new(r.max.x.max(x), r.max.y.max(y));
+            r.touches_edge |= x == xb.start || x == xb.end - 1 || y == yb.start || y == yb.end - 1;
+            r.touches_orig_edge |=
+                x <= xo.start || x >= xo.end - 1 || y <= yo.start || y >= yo.end - 1;
+            r.perimeter += [(0, -1), (-1, 0), (1, 0), (0, 1)]
+                .iter()
+                .filter(|(dx, dy)| !same(label, x + dx, y + dy))
+                .count();
+            // count corners: convex where neither orthogonal neighbor is in
+            // the region, concave where both are but the diagonal isn't
+            r.sides += [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+                .iter()
+                .filter(|(dx, dy)| {
+                    let h = same(label, x + dx, y);
+                    let v = same(label, x, y + dy);
+                    (!h && !v) || (h && v && !same(label, x + dx, y + dy))
+                })
+                .count();
+        }
+    }
+
+    // Connected regions of equal cells
+    pub fn regions(&self) -> Regions
+    where
+        T: PartialEq,
+    {
+        self.regions_ex(|_, _| true, |a, b| a == b)
+    }
+
+    // Connected regions of cells matching a predicate; other cells are
+    // labelled NO_REGION.
+    pub fn regions_where<F>(&self, predicate: F) -> Regions
+    where
+        F: Fn(Coord2D, &T) -> bool,
+    {
+        self.regions_ex(predicate, |_, _| true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::lines;
+
+    fn garden() -> Grid<char> {
+        Grid::from_input(&lines(&["AAAA", "BBCD", "BBCC", "EEEC"]), '.', 1)
+    }
+
+    #[test]
+    fn test_regions() {
+        let grid = garden();
+        let regions = grid.regions_where(|_, c| *c != '.');
+        assert_eq!(regions.regions.len(), 1);
+        assert!(!regions.regions[0].touches_edge);
+        assert!(regions.regions[0].touches_orig_edge);
+
+        let regions = grid.regions();
+        // the padding is a region of its own
+        let stats: Vec<(char, usize, usize, usize)> = regions
+            .regions
+            .iter()
+            .skip(1)
+            .map(|r| (grid.get_c(r.start), r.area, r.perimeter, r.sides))
+            .collect();
+        assert_eq!(
+            stats,
+            vec![
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4),
+            ]
+        );
+        let c = regions.region_at((2, 1)).unwrap();
+        assert_eq!((c.min, c.max), (Coord2D::new(2, 1), Coord2D::new(3, 3)));
+        assert!(regions.regions[0].touches_edge);
+        assert_eq!(regions.cells(c.label).count(), 4);
+        assert_eq!(grid.flood_fill((0, 1), |a, b| a == b).len(), 4);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::algorithm::dijkstra_ex;
+use crate::coords::{Coord2D, NEIGHBORS4};
+use crate::grid::Grid;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+// Searches over the 4-connected cells of a Grid. Closures are asked about
+// the cell being stepped into; sources count as reachable regardless.
+impl<T> Grid<T> {
+    fn distance_grid(&self) -> Grid<Option<u64>> {
+        let xb = self.x_bounds();
+        let yb = self.y_bounds();
+        Grid::new(xb.start, yb.start, xb.end - 1, yb.end - 1, None)
+    }
+
+    // Number of steps from the nearest source to every cell, or None for
+    // cells that can't be reached.
+    pub fn bfs_distances<I, C, F>(&self, sources: I, passable: F) -> Grid<Option<u64>>
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<Coord2D>,
+        F: Fn(Coord2D, &T) -> bool,
+    {
+        let mut dist = self.distance_grid();
+        let mut queue: VecDeque<Coord2D> = VecDeque::new();
+        for c in sources {
+            let c: Coord2D = c.into();
+            dist.set_c(c, Some(0));
+            queue.push_back(c);
+        }
+        while let Some(c) = queue.pop_front() {
+            let d = dist.get_c(c).unwrap();
+            for (n, val) in self.neighbors4(c) {
+                if dist.get_c(n).is_none() && passable(n, val) {
+                    dist.set_c(n, Some(d + 1));
+                    queue.push_back(n);
+                }
+            }
+        }
+        dist
+    }
+
+    pub fn bfs_distances_from<C, F>(&self, source: C, passable: F) -> Grid<Option<u64>>
+    where
+        C: Into<Coord2D>,
+        F: Fn(Coord2D, &T) -> bool,
+    {
+        self.bfs_distances([source], passable)
+    }
+
+    // Like bfs_distances, but entering a cell costs whatever `cost` says
+    // (None meaning the cell can't be entered).
+    pub fn cost_distances<I, C, F>(&self, sources: I, cost: F) -> Grid<Option<u64>>
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<Coord2D>,
+        F: Fn(Coord2D, &T) -> Option<u64>,
+    {
+        let mut dist = self.distance_grid();
+        let mut queue: BinaryHeap<(Reverse<u64>, Coord2D)> = BinaryHeap::new();
+        for c in sources {
+            let c: Coord2D = c.into();
+            dist.set_c(c, Some(0));
+            queue.push((Reverse(0), c));
+        }
+        while let Some((Reverse(d), c)) = queue.pop() {
+            if dist.get_c(c).is_some_and(|best| best < d) {
+                continue;
+            }
+            for (n, val) in self.neighbors4(c) {
+                if let Some(step) = cost(n, val) {
+                    let nd = d + step;
+                    if dist.get_c(n).is_none_or(|best| nd < best) {
+                        dist.set_c(n, Some(nd));
+                        queue.push((Reverse(nd), n));
+                    }
+                }
+            }
+        }
+        dist
+    }
+
+    // The cheapest path from start to end (both included) and its cost.
+    pub fn shortest_path<C1, C2, F>(
+        &self,
+        start: C1,
+        end: C2,
+        cost: F,
+    ) -> Option<(u64, Vec<Coord2D>)>
+    where
+        C1: Into<Coord2D>,
+        C2: Into<Coord2D>,
+        F: Fn(Coord2D, &T) -> Option<u64>,
+    {
+        let start: Coord2D = start.into();
+        let end: Coord2D = end.into();
+        let (total, prev) = dijkstra_ex(
+            start,
+            |c| *c == end,
+            |c| {
+                NEIGHBORS4
+                    .iter()
+                    .filter_map(|o| {
+                        let n = *c + *o;
+                        self.try_get_ref_c(n)
+                            .and_then(|val| cost(n, val))
+                            .map(|step| (n, step))
+                    })
+                    .collect()
+            },
+            false,
+        )?;
+        let mut path = vec![end];
+        let mut c = end;
+        while c != start {
+            c = *prev[&c].1.iter().min().unwrap();
+            path.push(c);
+        }
+        path.reverse();
+        Some((total, path))
+    }
+}
+
+impl Grid<Option<u64>> {
+    // Walks back from `end` through ever smaller distances to a source, for
+    // a distance grid from bfs_distances. The path runs source to end.
+    pub fn path_to<C>(&self, end: C) -> Option<Vec<Coord2D>>
+    where
+        C: Into<Coord2D>,
+    {
+        let mut c: Coord2D = end.into();
+        let mut d = self.try_get_c(c)??;
+        let mut path = vec![c];
+        while d > 0 {
+            (c, _) = self.neighbors4(c).find(|(_, nd)| **nd == Some(d - 1))?;
+            d -= 1;
+            path.push(c);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::lines;
+
+    fn maze() -> Grid<char> {
+        Grid::from_input(&lines(&["S..#", ".#..", "...E"]), '#', 0)
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = maze();
+        let dist = grid.bfs_distances_from((0, 0), |_, c| *c != '#');
+        assert_eq!(
+            dist.format_str(|d| d.map(|v| v.to_string()).unwrap_or("#".into())),
+            "012#\n1#34\n2345\n"
+        );
+        let path = dist.path_to((3, 2)).unwrap();
+        assert_eq!(path.len(), 6);
+        assert_eq!(path[0], Coord2D::new(0, 0));
+        assert_eq!(path[5], Coord2D::new(3, 2));
+        assert!(path.windows(2).all(|w| w[0].mdist_to(&w[1]) == 1));
+
+        let dist = grid.bfs_distances([(0, 0), (3, 2)], |_, c| *c != '#');
+        assert_eq!(dist.get(2, 1), Some(2));
+        assert_eq!(dist.path_to((2, 1)).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_weighted() {
+        let grid = maze();
+        // walking along the bottom row is expensive
+        let cost = |c: Coord2D, v: &char| match v {
+            '#' => None,
+            _ if c.y == 2 => Some(5),
+            _ => Some(1),
+        };
+        let (total, path) = grid.shortest_path((0, 0), (3, 2), cost).unwrap();
+        assert_eq!(total, 9);
+        assert_eq!(path[1..3], [Coord2D::new(1, 0), Coord2D::new(2, 0)]);
+        let dist = grid.cost_distances([(0, 0)], cost);
+        assert_eq!(dist.get(3, 2), Some(9));
+        assert_eq!(dist.get(0, 2), Some(6));
+        assert_eq!(grid.shortest_path((0, 0), (3, 0), cost), None);
+    }
+}
@@ -0,0 +1,279 @@
+use crate::coords::CDir;
+use crate::grid::{Grid, GridTransform};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Edge matching for tile puzzles. Edges are read left to right (N and S) or
+// top to bottom (W and E), so two tiles fit side by side when the E edge of
+// the left one equals the W edge of the right one, and likewise for S and N.
+
+impl<T: Clone> Grid<T> {
+    // One edge of the tile as it would be after applying xform
+    pub fn edge(&self, side: CDir, xform: GridTransform) -> Vec<T> {
+        let xb = self.x_bounds_xform(xform);
+        let yb = self.y_bounds_xform(xform);
+        match side {
+            CDir::N => xb.map(|x| self.get_xform(x, yb.start, xform)).collect(),
+            CDir::S => xb.map(|x| self.get_xform(x, yb.end - 1, xform)).collect(),
+            CDir::W => yb.map(|y| self.get_xform(xb.start, y, xform)).collect(),
+            CDir::E => yb.map(|y| self.get_xform(xb.end - 1, y, xform)).collect(),
+        }
+    }
+
+    // All four edges in N, E, S, W order
+    pub fn edges(&self, xform: GridTransform) -> [Vec<T>; 4] {
+        [CDir::N, CDir::E, CDir::S, CDir::W].map(|side| self.edge(side, xform))
+    }
+
+    // Whether `other` (under other_xform) can go on the given side of this
+    // tile (under xform)
+    pub fn fits(
+        &self,
+        xform: GridTransform,
+        side: CDir,
+        other: &Grid<T>,
+        other_xform: GridTransform,
+    ) -> bool
+    where
+        T: PartialEq,
+    {
+        self.edge(side, xform) == other.edge(-side, other_xform)
+    }
+
+    // The transforms of `other` that let it go on the given side of this tile
+    pub fn compatible(&self, side: CDir, other: &Grid<T>) -> Vec<GridTransform>
+    where
+        T: PartialEq,
+    {
+        let edge = self.edge(side, GridTransform::Identity);
+        GridTransform::all()
+            .into_iter()
+            .filter(|t| other.edge(-side, *t) == edge)
+            .collect()
+    }
+}
+
+// An edge and its reverse are the same edge once tiles can be flipped; this
+// picks the smaller of the two so either can be used as a hash key.
+pub fn canonical_edge<T: Clone + Ord>(edge: &[T]) -> Vec<T> {
+    let rev: Vec<T> = edge.iter().rev().cloned().collect();
+    if rev.as_slice() < edge {
+        rev
+    } else {
+        edge.to_vec()
+    }
+}
+
+// Maps each canonical edge to the tiles that have it. Edges with only one
+// tile are on the outside of the puzzle, so corners are the tiles with two
+// of those.
+pub fn edge_index<T: Clone + Ord + Hash>(tiles: &[Grid<T>]) -> HashMap<Vec<T>, Vec<usize>> {
+    let mut index: HashMap<Vec<T>, Vec<usize>> = HashMap::new();
+    for (i, tile) in tiles.iter().enumerate() {
+        for edge in tile.edges(GridTransform::Identity) {
+            index.entry(canonical_edge(&edge)).or_default().push(i);
+        }
+    }
+    index
+}
+
+// Arranges square tiles into a width x height grid so that all touching edges
+// match, trying every transform of every tile. The result gives the tile index
+// and transform for each position in reading order.
+pub fn assemble<T>(
+    tiles: &[Grid<T>],
+    width: usize,
+    height: usize,
+) -> Option<Vec<(usize, GridTransform)>>
+where
+    T: Clone + Eq + Hash,
+{
+    assert_eq!(tiles.len(), width * height);
+    // edges[tile][xform] in N, E, S, W order
+    let edges: Vec<Vec<[Vec<T>; 4]>> = tiles
+        .iter()
+        .map(|tile| {
+            let (xb, yb) = (tile.x_bounds(), tile.y_bounds());
+            assert_eq!(xb.end - xb.start, yb.end - yb.start, "tiles must be square");
+            GridTransform::all().map(|t| tile.edges(t)).to_vec()
+        })
+        .collect();
+    let mut by_west: HashMap<&[T], Vec<(usize, usize)>> = HashMap::new();
+    let mut by_north: HashMap<&[T], Vec<(usize, usize)>> = HashMap::new();
+    for (i, tile_edges) in edges.iter().enumerate() {
+        for (t, e) in tile_edges.iter().enumerate() {
+            by_north.entry(&e[0]).or_default().push((i, t));
+            by_west.entry(&e[3]).or_default().push((i, t));
+        }
+    }
+    let all: Vec<(usize, usize)> = (0..tiles.len())
+        .flat_map(|i| (0..8).map(move |t| (i, t)))
+        .collect();
+
+    fn search<T: Eq + Hash>(
+        width: usize,
+        edges: &[Vec<[Vec<T>; 4]>],
+        by_west: &HashMap<&[T], Vec<(usize, usize)>>,
+        by_north: &HashMap<&[T], Vec<(usize, usize)>>,
+        all: &[(usize, usize)],
+        placed: &mut Vec<(usize, usize)>,
+        used: &mut [bool],
+    ) -> bool {
+        let pos = placed.len();
+        if pos == used.len() {
+            return true;
+        }
+        let west = (!pos.is_multiple_of(width)).then(|| placed[pos - 1]);
+        let north = (pos >= width).then(|| placed[pos - width]);
+        let candidates: &[(usize, usize)] = match (west, north) {
+            (Some((i, t)), _) => by_west.get(edges[i][t][1].as_slice()).map_or(&[], |v| v),
+            (None, Some((i, t))) => by_north.get(edges[i][t][2].as_slice()).map_or(&[], |v| v),
+            (None, None) => all,
+        };
+        for &(i, t) in candidates {
+            if used[i] {
+                continue;
+            }
+            if let Some((ni, nt)) = north {
+                if edges[ni][nt][2] != edges[i][t][0] {
+                    continue;
+                }
+            }
+            used[i] = true;
+            placed.push((i, t));
+            if search(width, edges, by_west, by_north, all, placed, used) {
+                return true;
+            }
+            placed.pop();
+            used[i] = false;
+        }
+        false
+    }
+
+    let mut placed = Vec::with_capacity(tiles.len());
+    let mut used = vec![false; tiles.len()];
+    search(
+        width,
+        &edges,
+        &by_west,
+        &by_north,
+        &all,
+        &mut placed,
+        &mut used,
+    )
+    .then(|| {
+        let xforms = GridTransform::all();
+        placed.into_iter().map(|(i, t)| (i, xforms[t])).collect()
+    })
+}
+
+// Builds the picture from the result of assemble, cutting `trim` cells off
+// every side of each tile first.
+pub fn join_tiles<T: Clone>(
+    tiles: &[Grid<T>],
+    placement: &[(usize, GridTransform)],
+    width: usize,
+    trim: usize,
+) -> Grid<T> {
+    let parts: Vec<Grid<T>> = placement
+        .iter()
+        .map(|(i, t)| {
+            let tile = tiles[*i].transformed(*t);
+            let xb = tile.x_bounds();
+            let yb = tile.y_bounds();
+            let trim = trim as i64;
+            tile.extract(
+                xb.start + trim,
+                yb.start + trim,
+                xb.end - xb.start - 2 * trim,
+                yb.end - yb.start - 2 * trim,
+            )
+        })
+        .collect();
+    Grid::from_tiles(&parts, width, placement.len() / width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3x3 tiles of 4x4 cut from a 10x10 picture, overlapping by one cell so
+    // that neighbors share an edge
+    fn pieces() -> Vec<Grid<u32>> {
+        let mut picture: Grid<u32> = Grid::new(0, 0, 9, 9, 0);
+        let mut seed = 12345u32;
+        for y in 0..10 {
+            for x in 0..10 {
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                picture.set(x, y, seed >> 16);
+            }
+        }
+        (0..3)
+            .flat_map(|ty| (0..3).map(move |tx| (tx, ty)))
+            .map(|(tx, ty)| picture.extract(tx * 3, ty * 3, 4, 4))
+            .collect()
+    }
+
+    #[test]
+    fn test_edges() {
+        let tiles = pieces();
+        assert_eq!(
+            tiles[0].edge(CDir::E, GridTransform::Identity),
+            tiles[1].edge(CDir::W, GridTransform::Identity)
+        );
+        assert!(tiles[0].fits(
+            GridTransform::Identity,
+            CDir::S,
+            &tiles[3],
+            GridTransform::Identity
+        ));
+        assert_eq!(
+            tiles[0].compatible(CDir::E, &tiles[1]),
+            vec![GridTransform::Identity]
+        );
+        let rotated = tiles[1].rot90();
+        assert_eq!(
+            tiles[0].compatible(CDir::E, &rotated),
+            vec![GridTransform::Rot270]
+        );
+        assert_eq!(canonical_edge(&[3, 2, 1]), vec![1, 2, 3]);
+
+        let index = edge_index(&tiles);
+        let corners: Vec<usize> = (0..tiles.len())
+            .filter(|i| index.values().filter(|v| v == &&vec![*i]).count() == 2)
+            .collect();
+        assert_eq!(corners, vec![0, 2, 6, 8]);
+    }
+
+    #[test]
+    fn test_assemble() {
+        let tiles = pieces();
+        let scrambled: Vec<Grid<u32>> = tiles
+            .iter()
+            .rev()
+            .zip(GridTransform::all().into_iter().cycle())
+            .map(|(tile, t)| tile.transformed(t))
+            .collect();
+        let placement = assemble(&scrambled, 3, 3).unwrap();
+        let joined = join_tiles(&scrambled, &placement, 3, 0);
+        let expected = join_tiles(
+            &tiles,
+            &(0..9)
+                .map(|i| (i, GridTransform::Identity))
+                .collect::<Vec<_>>(),
+            3,
+            0,
+        );
+        assert!(GridTransform::all()
+            .into_iter()
+            .any(|t| joined.transformed(t).data() == expected.data()));
+        assert_eq!(join_tiles(&scrambled, &placement, 3, 1).x_bounds(), 0..6);
+
+        let mut broken = scrambled.clone();
+        broken[4].set(0, 1, 7);
+        broken[4].set(1, 0, 7);
+        broken[4].set(3, 2, 7);
+        broken[4].set(2, 3, 7);
+        assert_eq!(assemble(&broken, 3, 3), None);
+    }
+}
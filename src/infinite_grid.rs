@@ -6,7 +6,7 @@ use std::io::Write;
 use std::ops::Range;
 
 #[derive(Clone)]
-pub struct InfiniteGrid<T: Copy> {
+pub struct InfiniteGrid<T> {
     default: T,
     data: HashMap<(i64, i64), T>,
     x_range: Range<i64>,
@@ -14,7 +14,7 @@ pub struct InfiniteGrid<T: Copy> {
     flip_y: bool,
 }
 
-impl<T: Copy> InfiniteGrid<T> {
+impl<T> InfiniteGrid<T> {
     pub fn new(default_val: T) -> Self {
         Self {
             default: default_val,
@@ -25,60 +25,32 @@ impl<T: Copy> InfiniteGrid<T> {
         }
     }
 
-    pub fn from_input<F>(input: &[String], default_val: T, mapfunc: F) -> Self
-    where
-        F: Fn(char, i64, i64) -> Option<T>,
-    {
-        let mut inst = Self::new(default_val);
-        for (uy, line) in input.iter().enumerate() {
-            for (ux, c) in line.chars().enumerate() {
-                let x = ux as i64;
-                let y = uy as i64;
-                if let Some(val) = mapfunc(c, x, y) {
-                    inst.set(x, y, val);
-                };
-            }
-        }
-        inst
+    pub fn iter(&self) -> Iter<'_, (i64, i64), T> {
+        self.data.iter()
     }
 
-    pub fn from_other<U: Copy, F>(other: &InfiniteGrid<U>, default_val: T, mapfunc: F) -> Self
-    where
-        F: Fn(U) -> Option<T>,
-    {
-        let mut inst = Self::new(default_val);
-        for y in other.y_bounds() {
-            for x in other.x_bounds() {
-                let other_val = other.get(x, y);
-                if let Some(val) = mapfunc(other_val) {
-                    inst.set(x, y, val);
-                }
-            }
-        }
-        inst
+    pub fn iter_mut(&mut self) -> IterMut<'_, (i64, i64), T> {
+        self.data.iter_mut()
     }
 
-    pub fn iter(&self) -> Iter<(i64, i64), T> {
-        self.data.iter()
+    pub fn default_val(&self) -> &T {
+        &self.default
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<(i64, i64), T> {
-        self.data.iter_mut()
+    pub fn get_ref(&self, x: i64, y: i64) -> &T {
+        self.data.get(&(x, y)).unwrap_or(&self.default)
     }
 
-    pub fn get(&self, x: i64, y: i64) -> T {
-        if let Some(cell) = self.data.get(&(x, y)) {
-            *cell
-        } else {
-            self.default
-        }
-    }
-    pub fn get_c(&self, c: Coord2D) -> T {
-        self.get(c.x, c.y)
+    pub fn get_ref_c(&self, c: Coord2D) -> &T {
+        self.get_ref(c.x, c.y)
     }
 
     pub fn set(&mut self, x: i64, y: i64, val: T) {
         self.data.insert((x, y), val);
+        self.extend_bounds(x, y);
+    }
+
+    fn extend_bounds(&mut self, x: i64, y: i64) {
         if self.x_range.is_empty() {
             self.x_range.start = x;
             self.x_range.end = x + 1;
@@ -172,6 +144,66 @@ impl<T: Copy> InfiniteGrid<T> {
         self.flip_y = val;
     }
 
+    pub fn y_flipped(&self) -> bool {
+        self.flip_y
+    }
+}
+
+impl<T: Clone> InfiniteGrid<T> {
+    pub fn from_input<F>(input: &[String], default_val: T, mapfunc: F) -> Self
+    where
+        F: Fn(char, i64, i64) -> Option<T>,
+    {
+        let mut inst = Self::new(default_val);
+        for (uy, line) in input.iter().enumerate() {
+            for (ux, c) in line.chars().enumerate() {
+                let x = ux as i64;
+                let y = uy as i64;
+                if let Some(val) = mapfunc(c, x, y) {
+                    inst.set(x, y, val);
+                };
+            }
+        }
+        inst
+    }
+
+    pub fn from_other<U: Clone, F>(other: &InfiniteGrid<U>, default_val: T, mapfunc: F) -> Self
+    where
+        F: Fn(U) -> Option<T>,
+    {
+        let mut inst = Self::new(default_val);
+        for y in other.y_bounds() {
+            for x in other.x_bounds() {
+                let other_val = other.get(x, y);
+                if let Some(val) = mapfunc(other_val) {
+                    inst.set(x, y, val);
+                }
+            }
+        }
+        inst
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> T {
+        self.get_ref(x, y).clone()
+    }
+    pub fn get_c(&self, c: Coord2D) -> T {
+        self.get(c.x, c.y)
+    }
+
+    // Inserts a copy of the default value if the cell hasn't been set
+    pub fn get_mut(&mut self, x: i64, y: i64) -> &mut T {
+        if !self.data.contains_key(&(x, y)) {
+            self.extend_bounds(x, y);
+        }
+        self.data
+            .entry((x, y))
+            .or_insert_with(|| self.default.clone())
+    }
+
+    pub fn get_mut_c(&mut self, c: Coord2D) -> &mut T {
+        self.get_mut(c.x, c.y)
+    }
+
     pub fn dump_to_file<F>(&self, file: &mut dyn Write, formatter: F)
     where
         F: Fn(T) -> char,
@@ -1,13 +1,28 @@
 pub mod algorithm;
+pub mod automaton;
+pub mod bit_grid;
 pub mod bits;
+pub mod cache;
 pub mod coords;
 pub mod geom;
 pub mod grid;
+pub mod grid_pattern;
+pub mod grid_regions;
+pub mod grid_search;
+pub mod grid_tiles;
 pub mod infinite_grid;
 pub mod iter;
 pub mod math;
+pub mod ocr;
+pub mod pattern;
 pub mod range;
 pub mod read;
+pub mod render;
+pub mod runner;
+pub mod scaffold;
 pub mod vm_debugger;
 pub mod vm_display;
 pub mod vm_shell;
+
+#[cfg(test)]
+mod test_util;
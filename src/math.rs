@@ -1,9 +1,9 @@
 pub fn firstfac(x: u64) -> u64 {
-    if x % 2 == 0 {
+    if x.is_multiple_of(2) {
         return 2;
     };
     for n in (3..).step_by(2).take_while(|m| m * m <= x) {
-        if x % n == 0 {
+        if x.is_multiple_of(n) {
             return n;
         };
     }
@@ -30,7 +30,7 @@ pub fn prime_factors(x: u64) -> Vec<u64> {
         if current == m {
             break;
         }
-        while current % m == 0 {
+        while current.is_multiple_of(m) {
             current /= m;
         }
         if current == 1 {
@@ -0,0 +1,243 @@
+use crate::coords::Coord2D;
+use crate::grid::Grid;
+use crate::infinite_grid::InfiniteGrid;
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+// Reading the block capitals some puzzles draw as their answer, in either
+// the 6 row font (most years) or the 10 row one (2018 day 10). Letters are
+// split up at empty columns, so the spacing between them doesn't matter.
+
+#[rustfmt::skip]
+const FONT_6: &[(char, [&str; 6])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[rustfmt::skip]
+const FONT_10: &[(char, [&str; 10])] = &[
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+lazy_static! {
+    // Glyph drawings (rows joined by newlines) to letters, for both fonts
+    static ref GLYPHS: HashMap<String, char> = FONT_6
+        .iter()
+        .map(|(c, rows)| (rows.join("\n"), *c))
+        .chain(FONT_10.iter().map(|(c, rows)| (rows.join("\n"), *c)))
+        .collect();
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OcrError {
+    // what could be read, with '?' for each unrecognized glyph
+    pub partial: String,
+    // position in the text and drawing of each unrecognized glyph
+    pub unknown: Vec<(usize, String)>,
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Couldn't read {} of the letters in \"{}\":",
+            self.unknown.len(),
+            self.partial
+        )?;
+        for (idx, glyph) in &self.unknown {
+            write!(f, "\nletter {}:\n{glyph}", idx + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for OcrError {}
+
+// Reads the letters drawn by a set of lit cells
+pub fn ocr_points<I>(points: I) -> Result<String, OcrError>
+where
+    I: IntoIterator<Item = Coord2D>,
+{
+    let points: HashSet<Coord2D> = points.into_iter().collect();
+    let Some(min_x) = points.iter().map(|c| c.x).min() else {
+        return Ok(String::new());
+    };
+    let max_x = points.iter().map(|c| c.x).max().unwrap();
+    let min_y = points.iter().map(|c| c.y).min().unwrap();
+    let max_y = points.iter().map(|c| c.y).max().unwrap();
+    let lit_cols: HashSet<i64> = points.iter().map(|c| c.x).collect();
+
+    let mut partial = String::new();
+    let mut unknown = Vec::new();
+    let mut x = min_x;
+    while x <= max_x {
+        if !lit_cols.contains(&x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while lit_cols.contains(&x) {
+            x += 1;
+        }
+        let glyph = (min_y..=max_y)
+            .map(|y| {
+                (start..x)
+                    .map(|x| {
+                        if points.contains(&Coord2D::new(x, y)) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        match GLYPHS.get(&glyph) {
+            Some(c) => partial.push(*c),
+            None => {
+                unknown.push((partial.chars().count(), glyph));
+                partial.push('?');
+            }
+        }
+    }
+    if unknown.is_empty() {
+        Ok(partial)
+    } else {
+        Err(OcrError { partial, unknown })
+    }
+}
+
+pub fn ocr_set(points: &HashSet<Coord2D>) -> Result<String, OcrError> {
+    ocr_points(points.iter().copied())
+}
+
+impl Grid<bool> {
+    pub fn ocr(&self) -> Result<String, OcrError> {
+        ocr_points(
+            self.iter_with_coord()
+                .filter(|(v, _, _)| *v)
+                .map(|(_, x, y)| Coord2D::new(x, y)),
+        )
+    }
+}
+
+impl InfiniteGrid<bool> {
+    // Reads the grid the way print shows it, so with flip_y the rows are
+    // taken bottom to top
+    pub fn ocr(&self) -> Result<String, OcrError> {
+        let flip = if self.y_flipped() { -1 } else { 1 };
+        ocr_points(
+            self.iter()
+                .filter(|(_, v)| **v)
+                .map(|((x, y), _)| Coord2D::new(*x, *y * flip)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::lines;
+
+    #[test]
+    fn test_ocr() {
+        let input = lines(&[
+            ".##..###..#...#.####",
+            "#..#.#..#.#...#....#",
+            "#..#.###...#.#....#.",
+            "####.#..#...#....#..",
+            "#..#.#..#...#...#...",
+            "#..#.###....#...####",
+        ]);
+        let grid = Grid::from_input_map(&input, false, 1, |c| c == '#');
+        assert_eq!(grid.ocr(), Ok("ABYZ".into()));
+
+        let mut inf = InfiniteGrid::from_input(&input, false, |c, _, _| (c == '#').then_some(true));
+        assert_eq!(inf.ocr(), Ok("ABYZ".into()));
+        inf.flip_y(true);
+        assert!(inf.ocr().is_err());
+        // drawn bottom-up, i.e. with y increasing upwards
+        let rows: Vec<String> = input.iter().rev().cloned().collect();
+        let mut inf = InfiniteGrid::from_input(&rows, false, |c, _, _| (c == '#').then_some(true));
+        inf.flip_y(true);
+        assert_eq!(inf.ocr(), Ok("ABYZ".into()));
+
+        let set: HashSet<Coord2D> = grid
+            .iter_with_coord()
+            .filter(|(v, _, _)| *v)
+            .map(|(_, x, y)| Coord2D::new(x + 100, y - 50))
+            .collect();
+        assert_eq!(ocr_set(&set), Ok("ABYZ".into()));
+        assert_eq!(ocr_points([]), Ok(String::new()));
+
+        let big = lines(&[
+            "#....#..######",
+            "#....#.......#",
+            ".#..#........#",
+            ".#..#.......#.",
+            "..##.......#..",
+            "..##......#...",
+            ".#..#....#....",
+            ".#..#...#.....",
+            "#....#..#.....",
+            "#....#..######",
+        ]);
+        let grid = Grid::from_input_map(&big, false, 0, |c| c == '#');
+        assert_eq!(grid.ocr(), Ok("XZ".into()));
+    }
+
+    #[test]
+    fn test_unknown() {
+        let input = lines(&[
+            "#..#..#..####",
+            "#..#.#.#.#...",
+            "####.#.#.###.",
+            "#..#..#..#...",
+            "#..#..#..#...",
+            "#..#..#..####",
+        ]);
+        let grid = Grid::from_input_map(&input, false, 0, |c| c == '#');
+        let err = grid.ocr().unwrap_err();
+        assert_eq!(err.partial, "H?E");
+        assert_eq!(
+            err.unknown,
+            vec![(1, ".#.\n#.#\n#.#\n.#.\n.#.\n.#.".to_string())]
+        );
+        assert!(err
+            .to_string()
+            .starts_with("Couldn't read 1 of the letters in \"H?E\":\nletter 2:\n.#.\n"));
+    }
+}
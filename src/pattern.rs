@@ -0,0 +1,348 @@
+use crate::coords::{Coord2D, Coord3D};
+use crate::read::ParseErr;
+use regex::{Captures, Regex};
+
+#[doc(hidden)]
+pub use lazy_static::lazy_static;
+use std::ops::Range;
+
+// A declarative line format, e.g. "{name} ({weight}) -> {children*, }".
+//
+// Fields are written in braces and matched non-greedily:
+//   {name}       a required field
+//   {name?}      a field that may be empty (parses as None when it is)
+//   {name*SEP}   a list of values separated by SEP (whitespace if omitted)
+// Use {{ and }} for literal braces. Runs of whitespace in the literal text
+// match any amount of whitespace, and whitespace at either end of the
+// pattern is optional.
+pub struct LinePattern {
+    pattern: String,
+    re: Regex,
+    fields: Vec<FieldSpec>,
+}
+
+struct FieldSpec {
+    name: String,
+    sep: Option<String>,
+}
+
+impl LinePattern {
+    pub fn new(pattern: &str) -> Self {
+        let mut re_str = String::from("^");
+        if pattern.starts_with(char::is_whitespace) {
+            re_str.push_str(r"\s*");
+        }
+        let mut fields = Vec::new();
+        let mut chars = pattern.trim().chars().peekable();
+        let mut literal = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    re_str.push_str(&Self::literal_re(&literal));
+                    literal.clear();
+                    let mut spec = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => spec.push(c),
+                            None => panic!("Unclosed '{{' in pattern: {pattern}"),
+                        }
+                    }
+                    let (name, re, sep) = if let Some(name) = spec.strip_suffix('?') {
+                        (name, ".*?", None)
+                    } else if let Some((name, sep)) = spec.split_once('*') {
+                        (name, ".*?", Some(sep.to_string()))
+                    } else {
+                        (spec.as_str(), ".+?", None)
+                    };
+                    assert!(
+                        !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_'),
+                        "Invalid field name in pattern: {pattern}"
+                    );
+                    re_str.push_str(&format!("(?P<{name}>{re})"));
+                    fields.push(FieldSpec {
+                        name: name.into(),
+                        sep,
+                    });
+                }
+                '}' => panic!("Unmatched '}}' in pattern: {pattern}"),
+                _ => literal.push(c),
+            }
+        }
+        re_str.push_str(&Self::literal_re(&literal));
+        if pattern.ends_with(char::is_whitespace) {
+            re_str.push_str(r"\s*");
+        }
+        re_str.push('$');
+        Self {
+            pattern: pattern.into(),
+            re: Regex::new(&re_str).unwrap(),
+            fields,
+        }
+    }
+
+    fn literal_re(literal: &str) -> String {
+        let mut out = String::new();
+        let mut in_space = false;
+        for c in literal.chars() {
+            if c.is_whitespace() {
+                if !in_space {
+                    out.push_str(r"\s+");
+                }
+                in_space = true;
+            } else {
+                out.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+                in_space = false;
+            }
+        }
+        out
+    }
+
+    pub fn parse_line<'a>(&'a self, line: &'a str) -> Result<PatternMatch<'a>, ParseErr> {
+        self.re
+            .captures(line)
+            .map(|caps| PatternMatch {
+                pattern: self,
+                caps,
+            })
+            .ok_or_else(|| ParseErr::Err(format!("line does not match `{}`", self.pattern)))
+    }
+}
+
+pub struct PatternMatch<'a> {
+    pattern: &'a LinePattern,
+    caps: Captures<'a>,
+}
+
+impl PatternMatch<'_> {
+    pub fn get<T: PatternField>(&self, name: &str) -> Result<T, ParseErr> {
+        T::from_match(self, name)
+    }
+
+    pub fn str(&self, name: &str) -> Option<&str> {
+        self.caps.name(name).map(|m| m.as_str())
+    }
+
+    fn span(&self, name: &str) -> Option<(&str, Range<usize>)> {
+        self.caps.name(name).map(|m| (m.as_str(), m.range()))
+    }
+
+    fn sep(&self, name: &str) -> Option<&str> {
+        self.pattern
+            .fields
+            .iter()
+            .find(|f| f.name == name)
+            .and_then(|f| f.sep.as_deref())
+    }
+}
+
+// A single value that can appear in a pattern field.
+pub trait FieldValue: Sized {
+    fn parse_field(s: &str) -> Result<Self, String>;
+}
+
+// Implements FieldValue for types whose FromStr error is Display.
+#[macro_export]
+macro_rules! field_value_from_str {
+    ($($t:ty),+ $(,)?) => {
+        $(
+            impl $crate::pattern::FieldValue for $t {
+                fn parse_field(s: &str) -> Result<Self, String> {
+                    s.trim().parse::<$t>().map_err(|e| e.to_string())
+                }
+            }
+        )+
+    };
+}
+
+field_value_from_str!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char
+);
+
+impl FieldValue for String {
+    fn parse_field(s: &str) -> Result<Self, String> {
+        Ok(s.into())
+    }
+}
+
+impl FieldValue for Coord2D {
+    fn parse_field(s: &str) -> Result<Self, String> {
+        s.parse().map_err(|_| format!("invalid coordinate: {s}"))
+    }
+}
+
+impl FieldValue for Coord3D {
+    fn parse_field(s: &str) -> Result<Self, String> {
+        s.parse().map_err(|_| format!("invalid coordinate: {s}"))
+    }
+}
+
+// Something that can be pulled out of a PatternMatch by field name: a single
+// FieldValue, an Option of one, or a Vec of them.
+pub trait PatternField: Sized {
+    fn from_match(m: &PatternMatch, name: &str) -> Result<Self, ParseErr>;
+}
+
+fn parse_value<T: FieldValue>(name: &str, text: &str, span: Range<usize>) -> Result<T, ParseErr> {
+    T::parse_field(text).map_err(|e| ParseErr::ErrAt(format!("{name}: {e}"), span))
+}
+
+impl<T: FieldValue> PatternField for T {
+    fn from_match(m: &PatternMatch, name: &str) -> Result<Self, ParseErr> {
+        let (text, span) = m
+            .span(name)
+            .ok_or_else(|| ParseErr::Err(format!("pattern has no field `{name}`")))?;
+        parse_value(name, text, span)
+    }
+}
+
+impl<T: FieldValue> PatternField for Option<T> {
+    fn from_match(m: &PatternMatch, name: &str) -> Result<Self, ParseErr> {
+        match m.span(name) {
+            Some((text, span)) if !text.is_empty() => parse_value(name, text, span).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl<T: FieldValue> PatternField for Vec<T> {
+    fn from_match(m: &PatternMatch, name: &str) -> Result<Self, ParseErr> {
+        let Some((text, span)) = m.span(name) else {
+            return Ok(Vec::new());
+        };
+        let items: Vec<&str> = match m.sep(name) {
+            Some(sep) if !sep.is_empty() => text.split(sep).collect(),
+            _ => text.split_whitespace().collect(),
+        };
+        items
+            .into_iter()
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                // item is a subslice of text, so its offset gives the span
+                let start = span.start + (item.as_ptr() as usize - text.as_ptr() as usize);
+                parse_value(name, item, start..start + item.len())
+            })
+            .collect()
+    }
+}
+
+// Declares a struct along with a FromStr impl that parses it from a
+// LinePattern. Field names in the pattern correspond to struct fields.
+//
+//   line_pattern! {
+//       "{name} ({weight}) -> {children*, }" =>
+//       struct Input {
+//           name: String,
+//           weight: i64,
+//           children: Vec<String>,
+//       }
+//   }
+#[macro_export]
+macro_rules! line_pattern {
+    (
+        $pat:literal =>
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($fvis:vis $field:ident : $fty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($fvis $field: $fty),*
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::read::ParseErr;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $crate::pattern::lazy_static! {
+                    static ref PATTERN: $crate::pattern::LinePattern =
+                        $crate::pattern::LinePattern::new($pat);
+                }
+                let m = PATTERN.parse_line(s)?;
+                Ok(Self {
+                    $($field: m.get(stringify!($field))?),*
+                })
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read::test_input;
+
+    line_pattern! {
+        "{name} ({weight}) -> {children*, }" =>
+        #[derive(Debug, PartialEq)]
+        struct Prog {
+            name: String,
+            weight: i64,
+            children: Vec<String>,
+        }
+    }
+
+    line_pattern! {
+        "Valve {name} has flow rate={rate}; tunnel{s?} lead{_s?} to valve{_v?} {to*, }" =>
+        struct Valve {
+            name: String,
+            rate: u32,
+            s: Option<char>,
+            to: Vec<String>,
+        }
+    }
+
+    #[test]
+    fn test_line_pattern() {
+        let input: Vec<Prog> = test_input("abc (12) -> d, e\nfgh (3) -> ");
+        assert_eq!(
+            input[0],
+            Prog {
+                name: "abc".into(),
+                weight: 12,
+                children: vec!["d".into(), "e".into()],
+            }
+        );
+        assert_eq!(input[1].children, Vec::<String>::new());
+
+        let v: Valve = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB"
+            .parse()
+            .unwrap();
+        assert_eq!((v.name.as_str(), v.rate, v.s), ("AA", 0, Some('s')));
+        assert_eq!(v.to, vec!["DD", "II", "BB"]);
+        let v: Valve = "Valve HH has flow rate=22; tunnel leads to valve GG"
+            .parse()
+            .unwrap();
+        assert_eq!((v.s, v.to), (None, vec!["GG".to_string()]));
+    }
+
+    #[test]
+    fn test_field_error_span() {
+        let pat = LinePattern::new("{x},{y}");
+        let m = pat.parse_line("10,1z").unwrap();
+        assert_eq!(m.get::<i64>("x").unwrap(), 10);
+        match m.get::<i64>("y") {
+            Err(ParseErr::ErrAt(_, span)) => assert_eq!(span, 3..5),
+            _ => panic!("expected error with span"),
+        }
+        assert!(pat.parse_line("10").is_err());
+
+        let pat = LinePattern::new(" {x} -> {y} ");
+        assert_eq!(pat.parse_line("a -> b").unwrap().str("y"), Some("b"));
+        assert_eq!(pat.parse_line("  a -> b ").unwrap().str("x"), Some("a"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unclosed '{'")]
+    fn test_unclosed_field() {
+        LinePattern::new("{x},{y");
+    }
+}
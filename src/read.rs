@@ -1,28 +1,41 @@
+use flate2::read::GzDecoder;
+use itertools::{Either, Itertools};
 use lazy_static::lazy_static;
+use num::PrimInt;
 use regex::Regex;
 use std::any::Any;
+use std::error::Error;
 use std::fmt;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader, IsTerminal, Lines, Stdin, StdinLock};
 use std::iter;
 use std::iter::Iterator;
-use std::path::Path;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::vec::Vec;
 
 const EOL: &[char] = &['\n', '\r'];
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum ParseErr {
     Err(String),
     Warn(String),
+    // Same as Err/Warn, but carrying the byte span of the offending text
+    // within the line so the diagnostic can point at it.
+    ErrAt(String, Range<usize>),
+    WarnAt(String, Range<usize>),
     Skip,
 }
 
 impl fmt::Display for ParseErr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ParseErr::Err(msg) | ParseErr::Warn(msg) => write!(f, "{msg}"),
+            ParseErr::Err(msg)
+            | ParseErr::Warn(msg)
+            | ParseErr::ErrAt(msg, _)
+            | ParseErr::WarnAt(msg, _) => write!(f, "{msg}"),
             ParseErr::Skip => write!(f, "[skipped]"),
         }
     }
@@ -34,6 +47,10 @@ lazy_static! {
 lazy_static! {
     static ref EXE_RE: Regex = Regex::new(r"^day(\d+)").unwrap();
 }
+lazy_static! {
+    static ref INT_RE: Regex = Regex::new(r"-?\d+").unwrap();
+    static ref UINT_RE: Regex = Regex::new(r"\d+").unwrap();
+}
 
 fn stdinlock() -> StdinLock<'static> {
     STDIN.lock()
@@ -41,75 +58,514 @@ fn stdinlock() -> StdinLock<'static> {
 
 enum LineIters {
     File(Lines<BufReader<File>>),
+    Gzip(Lines<BufReader<GzDecoder<File>>>),
     Stdin(Lines<StdinLock<'static>>),
 }
 
-pub struct LineIter(LineIters);
+pub struct TryLineIter(LineIters);
+
+impl TryLineIter {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            Ok(Self(LineIters::Gzip(
+                BufReader::new(GzDecoder::new(file)).lines(),
+            )))
+        } else {
+            Ok(Self(LineIters::File(BufReader::new(file).lines())))
+        }
+    }
+
+    fn stdin() -> Self {
+        Self(LineIters::Stdin(stdinlock().lines()))
+    }
+}
+
+impl Iterator for TryLineIter {
+    type Item = io::Result<String>;
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            LineIters::File(i) => i.next(),
+            LineIters::Gzip(i) => i.next(),
+            LineIters::Stdin(i) => i.next(),
+        }
+    }
+}
+
+pub struct LineIter(TryLineIter);
 
 impl Iterator for LineIter {
     type Item = String;
     fn next(&mut self) -> Option<Self::Item> {
-        match &mut self.0 {
-            LineIters::File(i) => i.next().map(|x| x.unwrap()),
-            LineIters::Stdin(i) => i.next().map(|x| x.unwrap()),
+        self.0.next().map(|x| x.unwrap())
+    }
+}
+
+// The day number from a name like day05 or day5_alt, if it is one
+pub fn day_from_name(name: &str) -> Option<u32> {
+    EXE_RE.captures(name)?[1].parse().ok()
+}
+
+// The day number from an executable named dayNN, if that's what this is
+pub fn day_from_exe() -> Option<u32> {
+    let exe = PathBuf::from(std::env::args().next()?);
+    day_from_name(&exe.file_name()?.to_string_lossy())
+}
+
+// Returned (wrapped in an io::Error of kind NotFound) when none of the
+// candidate input files exist.
+#[derive(Debug)]
+pub struct InputNotFound {
+    pub tried: Vec<PathBuf>,
+}
+
+impl fmt::Display for InputNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No input file found. Tried:")?;
+        for path in &self.tried {
+            write!(f, "\n    {}", path.display())?;
         }
+        Ok(())
     }
 }
 
-pub fn input_lines() -> impl Iterator<Item = String> {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() == 2 {
-        let file = File::open(&args[1]).unwrap();
-        LineIter(LineIters::File(BufReader::new(file).lines()))
-    } else if !STDIN.is_terminal() {
-        let lock = stdinlock();
-        LineIter(LineIters::Stdin(lock.lines()))
-    } else {
-        let exe_name = Path::new(&args[0]).file_name().unwrap();
-        let inp_name = if let Some(m) = EXE_RE.captures(exe_name.to_str().unwrap()) {
-            format!("day{}.input", m.get(1).unwrap().as_str())
-        } else {
-            format!(
-                "{}.input",
-                Path::new(&args[0]).file_stem().unwrap().to_str().unwrap()
-            )
-        };
-        let file = File::open(inp_name).unwrap();
-        LineIter(LineIters::File(BufReader::new(file).lines()))
+impl Error for InputNotFound {}
+
+// Describes where puzzle input comes from. In order of precedence:
+//   - the single command line argument, if there is one
+//   - the environment variable (AOC_INPUT by default), naming either a file,
+//     a directory to search instead of the configured ones, or "-" for stdin
+//   - stdin, if it isn't a terminal
+//   - the first file matching one of the name patterns in one of the
+//     directories, with or without a .gz suffix
+//
+// Patterns may use {year}, {day}, {day:02} and {name} (the executable's file
+// stem). Patterns referring to something unknown are ignored. The day is
+// inferred from an executable named dayNN unless given explicitly.
+#[derive(Clone, Debug)]
+pub struct InputSource {
+    use_args: bool,
+    use_stdin: bool,
+    env_var: Option<String>,
+    dirs: Vec<PathBuf>,
+    patterns: Vec<String>,
+    year: Option<u32>,
+    day: Option<u32>,
+    name: Option<String>,
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+impl InputSource {
+    pub fn new() -> Self {
+        let exe = std::env::args().next().map(PathBuf::from);
+        let name = exe
+            .as_ref()
+            .and_then(|p| p.file_stem())
+            .map(|s| s.to_string_lossy().into_owned());
+        let day = day_from_exe();
+        Self {
+            use_args: true,
+            use_stdin: true,
+            env_var: Some("AOC_INPUT".into()),
+            dirs: vec![PathBuf::from(".")],
+            patterns: vec![
+                "day{day:02}.input".into(),
+                "day{day}.input".into(),
+                "{name}.input".into(),
+            ],
+            year: None,
+            day,
+            name,
+        }
+    }
+
+    pub fn use_args(mut self, val: bool) -> Self {
+        self.use_args = val;
+        self
+    }
+
+    pub fn use_stdin(mut self, val: bool) -> Self {
+        self.use_stdin = val;
+        self
+    }
+
+    pub fn env_var(mut self, var: Option<&str>) -> Self {
+        self.env_var = var.map(|v| v.into());
+        self
+    }
+
+    pub fn dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.dirs = vec![dir.as_ref().into()];
+        self
+    }
+
+    pub fn add_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.dirs.push(dir.as_ref().into());
+        self
+    }
+
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        self.patterns = vec![pattern.into()];
+        self
+    }
+
+    pub fn add_pattern(mut self, pattern: &str) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+
+    pub fn year(mut self, year: u32) -> Self {
+        self.year = Some(year);
+        self
+    }
+
+    pub fn day(mut self, day: u32) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    fn expand(&self, pattern: &str) -> Option<String> {
+        let mut out = pattern.to_string();
+        for (key, val) in [
+            ("{year}", self.year.map(|y| y.to_string())),
+            ("{day:02}", self.day.map(|d| format!("{d:02}"))),
+            ("{day}", self.day.map(|d| d.to_string())),
+            ("{name}", self.name.clone()),
+        ] {
+            if out.contains(key) {
+                out = out.replace(key, &val?);
+            }
+        }
+        Some(out)
+    }
+
+    // All the files that would be tried, in order, when searching the
+    // given directories.
+    fn candidates_in(&self, dirs: &[PathBuf]) -> Vec<PathBuf> {
+        let names: Vec<String> = self
+            .patterns
+            .iter()
+            .filter_map(|p| self.expand(p))
+            .unique()
+            .collect();
+        dirs.iter()
+            .flat_map(|dir| names.iter().map(move |n| dir.join(n)))
+            .flat_map(|path| {
+                let mut gz = path.clone().into_os_string();
+                gz.push(".gz");
+                [path, gz.into()]
+            })
+            .collect()
+    }
+
+    pub fn candidates(&self) -> Vec<PathBuf> {
+        self.candidates_in(&self.dirs)
+    }
+
+    fn search(&self, dirs: &[PathBuf]) -> io::Result<TryLineIter> {
+        let tried = self.candidates_in(dirs);
+        match tried.iter().find(|p| p.is_file()) {
+            Some(path) => TryLineIter::open(path),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                InputNotFound { tried },
+            )),
+        }
+    }
+
+    // A file named outright, which is an error if it doesn't exist
+    fn open_named(path: PathBuf) -> io::Result<TryLineIter> {
+        if !path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                InputNotFound { tried: vec![path] },
+            ));
+        }
+        TryLineIter::open(&path)
+    }
+
+    pub fn open(&self) -> io::Result<TryLineIter> {
+        if self.use_args {
+            let args: Vec<String> = std::env::args().collect();
+            if args.len() == 2 {
+                return Self::open_named(PathBuf::from(&args[1]));
+            }
+        }
+        if let Some(val) = self
+            .env_var
+            .as_ref()
+            .and_then(std::env::var_os)
+            .filter(|v| !v.is_empty())
+        {
+            let path = PathBuf::from(val);
+            if path.as_os_str() == "-" {
+                return Ok(TryLineIter::stdin());
+            } else if path.is_dir() {
+                return self.search(&[path]);
+            }
+            return Self::open_named(path);
+        }
+        if self.use_stdin && !STDIN.is_terminal() {
+            return Ok(TryLineIter::stdin());
+        }
+        self.search(&self.dirs)
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = String> {
+        LineIter(self.open().unwrap_or_else(|e| panic!("{e}")))
+    }
+}
+
+pub fn try_input_lines() -> io::Result<TryLineIter> {
+    InputSource::new().open()
+}
+
+pub fn input_lines() -> impl Iterator<Item = String> {
+    InputSource::new().lines()
+}
+
 impl ParseErr {
-    fn show_err(&self, line: &str) {
+    pub fn span(&self) -> Option<Range<usize>> {
         match self {
-            ParseErr::Err(e) => {
-                panic!("Invalid line: {}\nError: {e}", line.trim());
+            ParseErr::ErrAt(_, span) | ParseErr::WarnAt(_, span) => Some(span.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn diagnostic(&self, line: &str, line_num: usize) -> String {
+        let level = match self {
+            ParseErr::Err(_) | ParseErr::ErrAt(..) => "error",
+            _ => "warning",
+        };
+        render_diagnostic(level, &self.to_string(), line, line_num, self.span())
+    }
+
+    fn show_err(&self, line: &str, line_num: usize) {
+        match self {
+            ParseErr::Err(_) | ParseErr::ErrAt(..) => {
+                panic!("{}", self.diagnostic(line, line_num));
             }
-            ParseErr::Warn(e) => {
-                eprintln!("Invalid line: {}\nError: {e}", line.trim());
+            ParseErr::Warn(_) | ParseErr::WarnAt(..) => {
+                eprintln!("{}", self.diagnostic(line, line_num));
             }
             ParseErr::Skip => {}
         }
     }
 }
 
-fn parse_line<T>(line: &str) -> Option<T>
+// Renders a compiler-style message, e.g.
+//
+//   error: bad number
+//    --> line 3, column 5
+//     |
+//   3 | foo xyz bar
+//     |     ^^^
+fn render_diagnostic(
+    level: &str,
+    msg: &str,
+    line: &str,
+    line_num: usize,
+    span: Option<Range<usize>>,
+) -> String {
+    let line = line.trim_end_matches(EOL);
+    let gutter = " ".repeat(line_num.to_string().len());
+    let mut out = format!("{level}: {msg}\n");
+    match span.as_ref().and_then(|sp| span_columns(line, sp)) {
+        Some((col, _)) => out.push_str(&format!(
+            "{gutter}--> line {line_num}, column {}\n",
+            col + 1
+        )),
+        None => out.push_str(&format!("{gutter}--> line {line_num}\n")),
+    }
+    out.push_str(&format!("{gutter} |\n{line_num} | {line}"));
+    if let Some((col, width)) = span.as_ref().and_then(|sp| span_columns(line, sp)) {
+        out.push_str(&format!(
+            "\n{gutter} | {}{}",
+            " ".repeat(col),
+            "^".repeat(width)
+        ));
+    }
+    out
+}
+
+// Converts a byte span into a (char column, char width) pair, clamping it to
+// the line. The width is always at least 1 so there's something to point at.
+fn span_columns(line: &str, span: &Range<usize>) -> Option<(usize, usize)> {
+    let start = span.start.min(line.len());
+    let end = span.end.clamp(start, line.len());
+    let col = line.get(..start)?.chars().count();
+    let width = line.get(start..end)?.chars().count();
+    Some((col, width.max(1)))
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParseEventKind {
+    Warn,
+    Skip,
+    // The FromStr error wasn't a ParseErr so we can't say much about it.
+    Unknown,
+}
+
+#[derive(Clone, Debug)]
+pub struct ParseEvent {
+    pub kind: ParseEventKind,
+    pub line_num: usize,
+    pub line: String,
+    pub message: Option<String>,
+    pub span: Option<Range<usize>>,
+}
+
+impl ParseEvent {
+    // 1-based column of the start of the span, if there is one
+    pub fn column(&self) -> Option<usize> {
+        self.span
+            .as_ref()
+            .and_then(|sp| span_columns(&self.line, sp))
+            .map(|(col, _)| col + 1)
+    }
+}
+
+impl fmt::Display for ParseEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (level, msg) = match self.kind {
+            ParseEventKind::Warn => ("warning", self.message.as_deref().unwrap_or("")),
+            ParseEventKind::Skip => ("note", "line skipped"),
+            ParseEventKind::Unknown => ("warning", "invalid line"),
+        };
+        write!(
+            f,
+            "{}",
+            render_diagnostic(level, msg, &self.line, self.line_num, self.span.clone())
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ParseReport {
+    pub lines_read: usize,
+    pub events: Vec<ParseEvent>,
+}
+
+impl ParseReport {
+    pub fn is_clean(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn count(&self, kind: ParseEventKind) -> usize {
+        self.events.iter().filter(|e| e.kind == kind).count()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} lines read: {} warnings, {} skipped, {} invalid",
+            self.lines_read,
+            self.count(ParseEventKind::Warn),
+            self.count(ParseEventKind::Skip),
+            self.count(ParseEventKind::Unknown),
+        )
+    }
+
+    fn push(&mut self, kind: ParseEventKind, line: &str, line_num: usize, pe: Option<&ParseErr>) {
+        self.events.push(ParseEvent {
+            kind,
+            line_num,
+            line: line.trim_end_matches(EOL).into(),
+            message: pe.map(|e| e.to_string()),
+            span: pe.and_then(|e| e.span()),
+        });
+    }
+}
+
+impl fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ev in self
+            .events
+            .iter()
+            .filter(|e| e.kind != ParseEventKind::Skip)
+        {
+            writeln!(f, "{ev}")?;
+        }
+        write!(f, "{}", self.summary())
+    }
+}
+
+// Keeps track of where we are in the input while parsing. When `echo` is set,
+// warnings go to stderr as they're encountered (the behavior of the plain
+// readers); otherwise they're only collected in the report.
+struct LineParser {
+    line_num: usize,
+    report: ParseReport,
+    echo: bool,
+}
+
+impl LineParser {
+    fn new(echo: bool) -> Self {
+        Self {
+            line_num: 0,
+            report: ParseReport::default(),
+            echo,
+        }
+    }
+
+    fn next_line(&mut self) {
+        self.line_num += 1;
+        self.report.lines_read += 1;
+    }
+
+    fn parse<T>(&mut self, line: &str) -> Option<T>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Any,
+    {
+        match line.trim_end_matches('\n').parse::<T>() {
+            Ok(val) => Some(val),
+            Err(e) => {
+                let e_any = &e as &dyn Any;
+                if let Some(pe) = e_any.downcast_ref::<ParseErr>() {
+                    if self.echo {
+                        pe.show_err(line, self.line_num);
+                    } else if let ParseErr::Err(_) | ParseErr::ErrAt(..) = pe {
+                        panic!("{}", pe.diagnostic(line, self.line_num));
+                    }
+                    let kind = match pe {
+                        ParseErr::Skip => ParseEventKind::Skip,
+                        _ => ParseEventKind::Warn,
+                    };
+                    self.report.push(kind, line, self.line_num, Some(pe));
+                } else {
+                    if self.echo {
+                        eprintln!("Invalid line {}: {}", self.line_num, line.trim());
+                    }
+                    self.report
+                        .push(ParseEventKind::Unknown, line, self.line_num, None);
+                }
+                None
+            }
+        }
+    }
+}
+
+fn lines_with_report<T, I: Iterator<Item = String>>(
+    line_iter: I,
+    echo: bool,
+) -> (Vec<T>, ParseReport)
 where
     T: FromStr,
     <T as FromStr>::Err: Any,
 {
-    match line.trim_end_matches('\n').parse::<T>() {
-        Ok(val) => Some(val),
-        Err(e) => {
-            let e_any = &e as &dyn Any;
-            if let Some(pe) = e_any.downcast_ref::<ParseErr>() {
-                pe.show_err(line);
-            } else {
-                eprintln!("Invalid line: {}", line.trim());
-            }
-            None
-        }
-    }
+    let mut parser = LineParser::new(echo);
+    let data = line_iter
+        .flat_map(|l| {
+            parser.next_line();
+            parser.parse(l.as_str())
+        })
+        .collect();
+    (data, parser.report)
 }
 
 pub fn input_from_iter<T, I: Iterator<Item = String>>(line_iter: I) -> Vec<T>
@@ -117,7 +573,17 @@ where
     T: FromStr,
     <T as FromStr>::Err: Any,
 {
-    line_iter.flat_map(|l| parse_line(l.as_str())).collect()
+    lines_with_report(line_iter, true).0
+}
+
+pub fn input_from_iter_with_report<T, I: Iterator<Item = String>>(
+    line_iter: I,
+) -> (Vec<T>, ParseReport)
+where
+    T: FromStr,
+    <T as FromStr>::Err: Any,
+{
+    lines_with_report(line_iter, false)
 }
 
 pub fn input_as_string() -> String {
@@ -134,6 +600,13 @@ where
     input_from_iter(data.lines().map(|l| l.into()))
 }
 
+pub fn test_input_with_report<T: FromStr>(data: &str) -> (Vec<T>, ParseReport)
+where
+    <T as FromStr>::Err: Any,
+{
+    input_from_iter_with_report(data.lines().map(|l| l.into()))
+}
+
 pub fn read_input<T: FromStr>() -> Vec<T>
 where
     <T as FromStr>::Err: Any,
@@ -141,25 +614,53 @@ where
     input_from_iter(input_lines())
 }
 
-pub fn grouped_input_from_iter<T: FromStr, I: Iterator<Item = String>>(line_iter: I) -> Vec<Vec<T>>
+pub fn read_input_with_report<T: FromStr>() -> (Vec<T>, ParseReport)
 where
     <T as FromStr>::Err: Any,
 {
+    input_from_iter_with_report(input_lines())
+}
+
+fn grouped_with_report<T: FromStr, I: Iterator<Item = String>>(
+    line_iter: I,
+    echo: bool,
+) -> (Vec<Vec<T>>, ParseReport)
+where
+    <T as FromStr>::Err: Any,
+{
+    let mut parser = LineParser::new(echo);
     let mut data: Vec<Vec<T>> = Vec::new();
     let mut row: Vec<T> = Vec::new();
     for line in line_iter {
+        parser.next_line();
         let val = line.trim_end_matches(EOL);
         if val.is_empty() {
             data.push(row);
             row = Vec::new();
-        } else if let Some(v) = parse_line(val) {
+        } else if let Some(v) = parser.parse(val) {
             row.push(v);
         }
     }
     if !row.is_empty() {
         data.push(row);
     }
-    data
+    (data, parser.report)
+}
+
+pub fn grouped_input_from_iter<T: FromStr, I: Iterator<Item = String>>(line_iter: I) -> Vec<Vec<T>>
+where
+    <T as FromStr>::Err: Any,
+{
+    grouped_with_report(line_iter, true).0
+}
+
+pub fn grouped_input_from_iter_with_report<T: FromStr, I: Iterator<Item = String>>(
+    line_iter: I,
+) -> (Vec<Vec<T>>, ParseReport)
+where
+    <T as FromStr>::Err: Any,
+{
+    grouped_with_report(line_iter, false)
 }
 
 pub fn read_grouped_input<T: FromStr>() -> Vec<Vec<T>>
@@ -169,6 +670,13 @@ where
     grouped_input_from_iter(input_lines())
 }
 
+pub fn read_grouped_input_with_report<T: FromStr>() -> (Vec<Vec<T>>, ParseReport)
+where
+    <T as FromStr>::Err: Any,
+{
+    grouped_input_from_iter_with_report(input_lines())
+}
+
 pub fn grouped_test_input<T: FromStr>(data: &str) -> Vec<Vec<T>>
 where
     <T as FromStr>::Err: Any,
@@ -176,32 +684,63 @@ where
     grouped_input_from_iter(data.lines().map(|l| l.into()))
 }
 
-pub fn sectioned_input_from_iter<T1: FromStr, T2: FromStr, I: Iterator<Item = String>>(
+pub fn grouped_test_input_with_report<T: FromStr>(data: &str) -> (Vec<Vec<T>>, ParseReport)
+where
+    <T as FromStr>::Err: Any,
+{
+    grouped_input_from_iter_with_report(data.lines().map(|l| l.into()))
+}
+
+fn sectioned_with_report<T1: FromStr, T2: FromStr, I: Iterator<Item = String>>(
     mut line_iter: I,
-) -> (Vec<T1>, Vec<T2>)
+    echo: bool,
+) -> ((Vec<T1>, Vec<T2>), ParseReport)
 where
     <T1 as FromStr>::Err: Any,
     <T2 as FromStr>::Err: Any,
 {
+    let mut parser = LineParser::new(echo);
     let mut data1: Vec<T1> = Vec::new();
     let mut data2: Vec<T2> = Vec::new();
 
     for l in line_iter.by_ref() {
+        parser.next_line();
         let l = l.trim_end_matches(EOL);
         if l.is_empty() {
             break;
         }
-        if let Some(v) = parse_line(l) {
+        if let Some(v) = parser.parse(l) {
             data1.push(v);
         }
     }
     for l in line_iter {
+        parser.next_line();
         let l = l.trim_end_matches(EOL);
-        if let Some(v) = parse_line(l) {
+        if let Some(v) = parser.parse(l) {
             data2.push(v);
         }
     }
-    (data1, data2)
+    ((data1, data2), parser.report)
+}
+
+pub fn sectioned_input_from_iter<T1: FromStr, T2: FromStr, I: Iterator<Item = String>>(
+    line_iter: I,
+) -> (Vec<T1>, Vec<T2>)
+where
+    <T1 as FromStr>::Err: Any,
+    <T2 as FromStr>::Err: Any,
+{
+    sectioned_with_report(line_iter, true).0
+}
+
+pub fn sectioned_input_from_iter_with_report<T1: FromStr, T2: FromStr, I: Iterator<Item = String>>(
+    line_iter: I,
+) -> ((Vec<T1>, Vec<T2>), ParseReport)
+where
+    <T1 as FromStr>::Err: Any,
+    <T2 as FromStr>::Err: Any,
+{
+    sectioned_with_report(line_iter, false)
 }
 
 pub fn read_sectioned_input<T1: FromStr, T2: FromStr>() -> (Vec<T1>, Vec<T2>)
@@ -212,6 +751,15 @@ where
     sectioned_input_from_iter(input_lines())
 }
 
+pub fn read_sectioned_input_with_report<T1: FromStr, T2: FromStr>(
+) -> ((Vec<T1>, Vec<T2>), ParseReport)
+where
+    <T1 as FromStr>::Err: Any,
+    <T2 as FromStr>::Err: Any,
+{
+    sectioned_input_from_iter_with_report(input_lines())
+}
+
 pub fn sectioned_test_input<T1: FromStr, T2: FromStr>(data: &str) -> (Vec<T1>, Vec<T2>)
 where
     <T1 as FromStr>::Err: Any,
@@ -219,3 +767,595 @@ where
 {
     sectioned_input_from_iter(data.lines().map(|l| l.into()))
 }
+
+pub fn sectioned_test_input_with_report<T1: FromStr, T2: FromStr>(
+    data: &str,
+) -> ((Vec<T1>, Vec<T2>), ParseReport)
+where
+    <T1 as FromStr>::Err: Any,
+    <T2 as FromStr>::Err: Any,
+{
+    sectioned_input_from_iter_with_report(data.lines().map(|l| l.into()))
+}
+
+// Input split into blank-line-separated sections, one element type per
+// section. Implemented for tuples of 2 to 6 FromStr types; the output is the
+// matching tuple of Vecs. As with sectioned_input_from_iter, the last section
+// takes everything that's left.
+pub trait Sections {
+    type Output;
+    const COUNT: usize;
+    fn parse_sections(sections: Vec<Vec<(usize, String)>>) -> Self::Output;
+}
+
+fn split_sections<I: Iterator<Item = String>>(
+    line_iter: I,
+    count: usize,
+) -> Vec<Vec<(usize, String)>> {
+    let mut sections: Vec<Vec<(usize, String)>> = vec![Vec::new()];
+    for (idx, line) in line_iter.enumerate() {
+        let line = line.trim_end_matches(EOL);
+        if line.is_empty() && sections.len() < count {
+            sections.push(Vec::new());
+        } else {
+            sections.last_mut().unwrap().push((idx + 1, line.into()));
+        }
+    }
+    sections.resize(count, Vec::new());
+    sections
+}
+
+fn parse_section<T: FromStr>(lines: &[(usize, String)]) -> Vec<T>
+where
+    <T as FromStr>::Err: Any,
+{
+    let mut parser = LineParser::new(true);
+    lines
+        .iter()
+        .flat_map(|(line_num, line)| {
+            parser.line_num = *line_num;
+            parser.parse(line)
+        })
+        .collect()
+}
+
+macro_rules! impl_sections {
+    ($count:literal; $($t:ident $idx:tt),+) => {
+        impl<$($t: FromStr),+> Sections for ($($t,)+)
+        where
+            $(<$t as FromStr>::Err: Any,)+
+        {
+            type Output = ($(Vec<$t>,)+);
+            const COUNT: usize = $count;
+            fn parse_sections(sections: Vec<Vec<(usize, String)>>) -> Self::Output {
+                ($(parse_section::<$t>(&sections[$idx]),)+)
+            }
+        }
+    };
+}
+
+impl_sections!(2; T1 0, T2 1);
+impl_sections!(3; T1 0, T2 1, T3 2);
+impl_sections!(4; T1 0, T2 1, T3 2, T4 3);
+impl_sections!(5; T1 0, T2 1, T3 2, T4 3, T5 4);
+impl_sections!(6; T1 0, T2 1, T3 2, T4 3, T5 4, T6 5);
+
+pub fn multi_sectioned_input_from_iter<S: Sections, I: Iterator<Item = String>>(
+    line_iter: I,
+) -> S::Output {
+    S::parse_sections(split_sections(line_iter, S::COUNT))
+}
+
+pub fn read_multi_sectioned_input<S: Sections>() -> S::Output {
+    multi_sectioned_input_from_iter::<S, _>(input_lines())
+}
+
+pub fn multi_sectioned_test_input<S: Sections>(data: &str) -> S::Output {
+    multi_sectioned_input_from_iter::<S, _>(data.lines().map(|l| l.into()))
+}
+
+// Pulls every integer out of a string, ignoring whatever is around them.
+// A '-' directly before the digits makes the number negative; use
+// extract_uints when '-' is just a separator (as in "1-3").
+// Panics on a number that doesn't fit in T; the _array and _tuple variants
+// return None for those instead.
+pub fn extract_ints<T: PrimInt + FromStr>(s: &str) -> Vec<T> {
+    parse_all(&INT_RE, s)
+}
+
+pub fn extract_uints<T: PrimInt + FromStr>(s: &str) -> Vec<T> {
+    parse_all(&UINT_RE, s)
+}
+
+fn parse_all<T: FromStr>(re: &Regex, s: &str) -> Vec<T> {
+    re.find_iter(s)
+        .map(|m| {
+            m.as_str().parse().unwrap_or_else(|_| {
+                panic!(
+                    "{} doesn't fit in {}",
+                    m.as_str(),
+                    std::any::type_name::<T>()
+                )
+            })
+        })
+        .collect()
+}
+
+fn first_n<T: FromStr, const N: usize>(re: &Regex, s: &str) -> Option<[T; N]> {
+    let vals: Vec<T> = re
+        .find_iter(s)
+        .take(N)
+        .map(|m| m.as_str().parse().ok())
+        .collect::<Option<_>>()?;
+    vals.try_into().ok()
+}
+
+// The first N integers of the string, or None if there are fewer than N or
+// one of them doesn't fit in T
+pub fn extract_ints_array<T: PrimInt + FromStr, const N: usize>(s: &str) -> Option<[T; N]> {
+    first_n(&INT_RE, s)
+}
+
+pub fn extract_uints_array<T: PrimInt + FromStr, const N: usize>(s: &str) -> Option<[T; N]> {
+    first_n(&UINT_RE, s)
+}
+
+// Tuples of integer types that can be filled from the numbers in a string,
+// e.g. extract_int_tuple::<(usize, usize, i32)>("move 3 from 1 to -2").
+// Implemented for tuples of 2 to 6 PrimInt types.
+pub trait IntTuple: Sized {
+    fn from_tokens(tokens: &[&str]) -> Option<Self>;
+}
+
+macro_rules! impl_int_tuple {
+    ($($t:ident $idx:tt),+) => {
+        impl<$($t: PrimInt + FromStr),+> IntTuple for ($($t,)+) {
+            fn from_tokens(tokens: &[&str]) -> Option<Self> {
+                Some(($(tokens.get($idx)?.parse::<$t>().ok()?,)+))
+            }
+        }
+    };
+}
+
+impl_int_tuple!(T1 0, T2 1);
+impl_int_tuple!(T1 0, T2 1, T3 2);
+impl_int_tuple!(T1 0, T2 1, T3 2, T4 3);
+impl_int_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4);
+impl_int_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5);
+
+pub fn extract_int_tuple<T: IntTuple>(s: &str) -> Option<T> {
+    T::from_tokens(&INT_RE.find_iter(s).map(|m| m.as_str()).collect::<Vec<_>>())
+}
+
+pub fn extract_uint_tuple<T: IntTuple>(s: &str) -> Option<T> {
+    T::from_tokens(&UINT_RE.find_iter(s).map(|m| m.as_str()).collect::<Vec<_>>())
+}
+
+pub fn ints_from_iter<I: Iterator<Item = String>>(line_iter: I) -> Vec<Vec<i64>> {
+    line_iter.map(|l| extract_ints(&l)).collect()
+}
+
+pub fn read_input_ints() -> Vec<Vec<i64>> {
+    ints_from_iter(input_lines())
+}
+
+pub fn test_input_ints(data: &str) -> Vec<Vec<i64>> {
+    ints_from_iter(data.lines().map(|l| l.into()))
+}
+
+#[derive(Debug)]
+pub enum InputError<E> {
+    Io(io::Error),
+    Parse {
+        line_num: usize,
+        line: String,
+        err: E,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for InputError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io(e) => write!(f, "I/O error: {e}"),
+            InputError::Parse {
+                line_num,
+                line,
+                err,
+            } => write!(f, "Invalid line {line_num}: {}\nError: {err}", line.trim()),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> Error for InputError<E> {}
+
+impl<E> From<io::Error> for InputError<E> {
+    fn from(e: io::Error) -> Self {
+        InputError::Io(e)
+    }
+}
+
+impl<E> InputError<E> {
+    pub fn map_err<F, E2>(self, func: F) -> InputError<E2>
+    where
+        F: FnOnce(E) -> E2,
+    {
+        match self {
+            InputError::Io(e) => InputError::Io(e),
+            InputError::Parse {
+                line_num,
+                line,
+                err,
+            } => InputError::Parse {
+                line_num,
+                line,
+                err: func(err),
+            },
+        }
+    }
+}
+
+// Like LineParser::parse, but hands back errors instead of panicking. Warn and Skip
+// still drop the line (with Warn being reported to stderr).
+fn try_parse_line<T>(line: &str, line_num: usize) -> Result<Option<T>, InputError<T::Err>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Any,
+{
+    match line.trim_end_matches('\n').parse::<T>() {
+        Ok(val) => Ok(Some(val)),
+        Err(e) => {
+            let e_any = &e as &dyn Any;
+            match e_any.downcast_ref::<ParseErr>() {
+                Some(ParseErr::Skip) => Ok(None),
+                Some(pe @ (ParseErr::Warn(_) | ParseErr::WarnAt(..))) => {
+                    pe.show_err(line, line_num);
+                    Ok(None)
+                }
+                _ => Err(InputError::Parse {
+                    line_num,
+                    line: line.into(),
+                    err: e,
+                }),
+            }
+        }
+    }
+}
+
+fn try_parse_lines<T, I>(line_iter: I) -> Result<Vec<T>, InputError<T::Err>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Any,
+    I: Iterator<Item = io::Result<String>>,
+{
+    let mut data = Vec::new();
+    for (idx, line) in line_iter.enumerate() {
+        if let Some(v) = try_parse_line(&line?, idx + 1)? {
+            data.push(v);
+        }
+    }
+    Ok(data)
+}
+
+pub fn try_input_from_iter<T, I: Iterator<Item = String>>(
+    line_iter: I,
+) -> Result<Vec<T>, InputError<T::Err>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Any,
+{
+    try_parse_lines(line_iter.map(Ok))
+}
+
+pub fn try_input_as_string() -> io::Result<String> {
+    Ok(try_input_lines()?
+        .chain(iter::once(Ok("".into())))
+        .collect::<io::Result<Vec<String>>>()?
+        .join("\n"))
+}
+
+pub fn try_test_input<T: FromStr>(data: &str) -> Result<Vec<T>, InputError<T::Err>>
+where
+    <T as FromStr>::Err: Any,
+{
+    try_input_from_iter(data.lines().map(|l| l.into()))
+}
+
+pub fn try_read_input<T: FromStr>() -> Result<Vec<T>, InputError<T::Err>>
+where
+    <T as FromStr>::Err: Any,
+{
+    try_parse_lines(try_input_lines()?)
+}
+
+fn try_parse_grouped<T, I>(line_iter: I) -> Result<Vec<Vec<T>>, InputError<T::Err>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Any,
+    I: Iterator<Item = io::Result<String>>,
+{
+    let mut data: Vec<Vec<T>> = Vec::new();
+    let mut row: Vec<T> = Vec::new();
+    for (idx, line) in line_iter.enumerate() {
+        let line = line?;
+        let val = line.trim_end_matches(EOL);
+        if val.is_empty() {
+            data.push(row);
+            row = Vec::new();
+        } else if let Some(v) = try_parse_line(val, idx + 1)? {
+            row.push(v);
+        }
+    }
+    if !row.is_empty() {
+        data.push(row);
+    }
+    Ok(data)
+}
+
+pub fn try_grouped_input_from_iter<T: FromStr, I: Iterator<Item = String>>(
+    line_iter: I,
+) -> Result<Vec<Vec<T>>, InputError<T::Err>>
+where
+    <T as FromStr>::Err: Any,
+{
+    try_parse_grouped(line_iter.map(Ok))
+}
+
+pub fn try_read_grouped_input<T: FromStr>() -> Result<Vec<Vec<T>>, InputError<T::Err>>
+where
+    <T as FromStr>::Err: Any,
+{
+    try_parse_grouped(try_input_lines()?)
+}
+
+pub fn try_grouped_test_input<T: FromStr>(data: &str) -> Result<Vec<Vec<T>>, InputError<T::Err>>
+where
+    <T as FromStr>::Err: Any,
+{
+    try_grouped_input_from_iter(data.lines().map(|l| l.into()))
+}
+
+pub type SectionedResult<T1, T2> =
+    Result<(Vec<T1>, Vec<T2>), InputError<Either<<T1 as FromStr>::Err, <T2 as FromStr>::Err>>>;
+
+fn try_parse_sectioned<T1, T2, I>(line_iter: I) -> SectionedResult<T1, T2>
+where
+    T1: FromStr,
+    T2: FromStr,
+    <T1 as FromStr>::Err: Any,
+    <T2 as FromStr>::Err: Any,
+    I: Iterator<Item = io::Result<String>>,
+{
+    let mut data1: Vec<T1> = Vec::new();
+    let mut data2: Vec<T2> = Vec::new();
+    let mut line_iter = line_iter.enumerate();
+
+    for (idx, l) in line_iter.by_ref() {
+        let l = l?;
+        let l = l.trim_end_matches(EOL);
+        if l.is_empty() {
+            break;
+        }
+        if let Some(v) = try_parse_line(l, idx + 1).map_err(|e| e.map_err(Either::Left))? {
+            data1.push(v);
+        }
+    }
+    for (idx, l) in line_iter {
+        let l = l?;
+        let l = l.trim_end_matches(EOL);
+        if let Some(v) = try_parse_line(l, idx + 1).map_err(|e| e.map_err(Either::Right))? {
+            data2.push(v);
+        }
+    }
+    Ok((data1, data2))
+}
+
+pub fn try_sectioned_input_from_iter<T1: FromStr, T2: FromStr, I: Iterator<Item = String>>(
+    line_iter: I,
+) -> SectionedResult<T1, T2>
+where
+    <T1 as FromStr>::Err: Any,
+    <T2 as FromStr>::Err: Any,
+{
+    try_parse_sectioned(line_iter.map(Ok))
+}
+
+pub fn try_read_sectioned_input<T1: FromStr, T2: FromStr>() -> SectionedResult<T1, T2>
+where
+    <T1 as FromStr>::Err: Any,
+    <T2 as FromStr>::Err: Any,
+{
+    try_parse_sectioned(try_input_lines()?)
+}
+
+pub fn try_sectioned_test_input<T1: FromStr, T2: FromStr>(data: &str) -> SectionedResult<T1, T2>
+where
+    <T1 as FromStr>::Err: Any,
+    <T2 as FromStr>::Err: Any,
+{
+    try_sectioned_input_from_iter(data.lines().map(|l| l.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    struct Num(i64);
+
+    impl FromStr for Num {
+        type Err = ParseErr;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            if s.starts_with('#') {
+                return Err(ParseErr::Skip);
+            }
+            match s.find("x") {
+                Some(pos) => Err(ParseErr::WarnAt("unexpected x".into(), pos..pos + 1)),
+                None => Ok(Num(s
+                    .parse()
+                    .map_err(|_| ParseErr::Warn("not a number".into()))?)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let (data, report) = test_input_with_report::<Num>("1\n# comment\n12x4\n5");
+        assert_eq!(data.iter().map(|n| n.0).collect::<Vec<_>>(), vec![1, 5]);
+        assert_eq!(report.lines_read, 4);
+        assert_eq!(report.count(ParseEventKind::Skip), 1);
+        assert_eq!(report.count(ParseEventKind::Warn), 1);
+        let warn = &report.events[1];
+        assert_eq!(warn.line_num, 3);
+        assert_eq!(warn.column(), Some(3));
+        assert_eq!(
+            warn.to_string(),
+            "warning: unexpected x\n --> line 3, column 3\n  |\n3 | 12x4\n  |   ^"
+        );
+
+        let (data, report) = test_input_with_report::<i64>("1\nfoo");
+        assert_eq!(data, vec![1]);
+        assert_eq!(report.events[0].kind, ParseEventKind::Unknown);
+        assert_eq!(report.events[0].line_num, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "--> line 2")]
+    fn test_err_has_line_number() {
+        struct Strict;
+        impl FromStr for Strict {
+            type Err = ParseErr;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                if s == "ok" {
+                    Ok(Strict)
+                } else {
+                    Err(ParseErr::Err("bad".into()))
+                }
+            }
+        }
+        test_input::<Strict>("ok\nbad");
+    }
+
+    #[test]
+    fn test_multi_sectioned() {
+        let (a, b, c) = multi_sectioned_test_input::<(i64, String, char)>("1\n2\n\nfoo\n\nx\ny");
+        assert_eq!(a, vec![1, 2]);
+        assert_eq!(b, vec!["foo".to_string()]);
+        assert_eq!(c, vec!['x', 'y']);
+
+        // missing trailing sections come back empty
+        let (a, b, c, d) = multi_sectioned_test_input::<(i64, i64, i64, i64)>("1\n\n2");
+        assert_eq!((a, b), (vec![1], vec![2]));
+        assert!(c.is_empty() && d.is_empty());
+    }
+
+    #[test]
+    fn test_extract_ints() {
+        let line = "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15";
+        assert_eq!(extract_ints::<i64>(line), vec![2, -18, -2, 15]);
+        assert_eq!(extract_uints::<u8>(line), vec![2, 18, 2, 15]);
+        assert_eq!(extract_uints_array::<usize, 2>("1-3 a: abc"), Some([1, 3]));
+        assert_eq!(extract_ints_array::<i32, 3>("1,2"), None);
+        assert_eq!(extract_ints_array::<u8, 3>("1 300 5 7"), None);
+        assert_eq!(extract_uints_array::<u8, 2>("1 5 300"), Some([1, 5]));
+        assert_eq!(extract_uint_tuple::<(u8, u8)>("300 1 5"), None);
+        assert_eq!(
+            extract_uint_tuple::<(usize, usize, u8)>("move 3 from 1 to 2"),
+            Some((3, 1, 2))
+        );
+        assert_eq!(extract_int_tuple::<(i64, i64)>("nothing here"), None);
+        assert_eq!(
+            test_input_ints("1 2\n\n-3"),
+            vec![vec![1, 2], vec![], vec![-3]]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "300 doesn't fit in u8")]
+    fn test_extract_overflow() {
+        extract_uints::<u8>("1 2 300 4");
+    }
+
+    #[test]
+    fn test_input_source() {
+        let tmp = TempDir::new("src");
+        std::fs::create_dir_all(tmp.join("2023")).unwrap();
+        let src = InputSource::new()
+            .dir(tmp.path())
+            .pattern("{year}/day{day:02}.txt")
+            .add_pattern("{year}/missing.txt")
+            .year(2023)
+            .day(5);
+        assert_eq!(
+            src.candidates(),
+            vec![
+                tmp.join("2023/day05.txt"),
+                tmp.join("2023/day05.txt.gz"),
+                tmp.join("2023/missing.txt"),
+                tmp.join("2023/missing.txt.gz"),
+            ]
+        );
+
+        let err = src.search(&src.dirs).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().contains("2023/missing.txt.gz"));
+        let err = InputSource::open_named(tmp.join("nope.txt")).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().contains("Tried:") && err.to_string().contains("nope.txt"));
+
+        let file = File::create(tmp.join("2023/day05.txt.gz")).unwrap();
+        let mut enc = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        io::Write::write_all(&mut enc, b"1\n2\n").unwrap();
+        enc.finish().unwrap();
+        let lines: Vec<String> = src.search(&src.dirs).unwrap().map(|l| l.unwrap()).collect();
+        assert_eq!(lines, vec!["1", "2"]);
+
+        // patterns needing an unknown year are dropped
+        assert_eq!(
+            InputSource::new()
+                .dir(tmp.path())
+                .pattern("{year}/x")
+                .candidates(),
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[test]
+    fn test_try_input() {
+        let data: Vec<i64> = try_test_input("1\n2\n3").unwrap();
+        assert_eq!(data, vec![1, 2, 3]);
+
+        let err = try_test_input::<i64>("1\n2\nthree\n4").unwrap_err();
+        match err {
+            InputError::Parse { line_num, line, .. } => {
+                assert_eq!(line_num, 3);
+                assert_eq!(line, "three");
+            }
+            _ => panic!("expected parse error"),
+        }
+    }
+
+    #[test]
+    fn test_try_grouped_input() {
+        let data: Vec<Vec<i64>> = try_grouped_test_input("1\n2\n\n3").unwrap();
+        assert_eq!(data, vec![vec![1, 2], vec![3]]);
+
+        let err = try_grouped_test_input::<i64>("1\n2\n\nx").unwrap_err();
+        assert!(matches!(err, InputError::Parse { line_num: 4, .. }));
+    }
+
+    #[test]
+    fn test_try_sectioned_input() {
+        let (a, b): (Vec<i64>, Vec<String>) = try_sectioned_test_input("1\n2\n\nfoo").unwrap();
+        assert_eq!(a, vec![1, 2]);
+        assert_eq!(b, vec!["foo".to_string()]);
+
+        let err = try_sectioned_test_input::<String, i64>("a\n\n1\nb").unwrap_err();
+        match err {
+            InputError::Parse { line_num, err, .. } => {
+                assert_eq!(line_num, 4);
+                assert!(matches!(err, Either::Right(_)));
+            }
+            _ => panic!("expected parse error"),
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use crate::grid::Grid;
+use crate::infinite_grid::InfiniteGrid;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// Rendering grids to image files for looking at them outside the terminal.
+// A closure picks the color of each cell, and every cell becomes one pixel
+// (or a square block of them after Image::scaled).
+
+pub type Rgb = [u8; 3];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ImageFormat {
+    Ppm,
+    Pgm,
+    Png,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Pgm => "pgm",
+            ImageFormat::Png => "png",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "ppm" => Some(ImageFormat::Ppm),
+            "pgm" => Some(ImageFormat::Pgm),
+            "png" => Some(ImageFormat::Png),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    // rows top to bottom
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, fill: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width * height],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    // Each pixel blown up to a factor x factor square
+    pub fn scaled(&self, factor: usize) -> Self {
+        let mut pixels = Vec::with_capacity(self.pixels.len() * factor * factor);
+        // a zero-width image has no pixels, but chunks() can't take a 0
+        for row in self.pixels.chunks(self.width.max(1)) {
+            for _ in 0..factor {
+                for p in row {
+                    pixels.extend(std::iter::repeat_n(*p, factor));
+                }
+            }
+        }
+        Self {
+            width: self.width * factor,
+            height: self.height * factor,
+            pixels,
+        }
+    }
+
+    fn rgb_bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flatten().copied().collect()
+    }
+
+    fn gray_bytes(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .map(|[r, g, b]| ((*r as u32 * 299 + *g as u32 * 587 + *b as u32 * 114) / 1000) as u8)
+            .collect()
+    }
+
+    // Binary (P6) PPM
+    pub fn write_ppm(&self, out: &mut dyn Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.rgb_bytes())
+    }
+
+    // Binary (P5) PGM, converting colors to their luminance
+    pub fn write_pgm(&self, out: &mut dyn Write) -> io::Result<()> {
+        write!(out, "P5\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.gray_bytes())
+    }
+
+    pub fn write_png(&self, out: &mut dyn Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.rgb_bytes())?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    pub fn write(&self, out: &mut dyn Write, format: ImageFormat) -> io::Result<()> {
+        match format {
+            ImageFormat::Ppm => self.write_ppm(out),
+            ImageFormat::Pgm => self.write_pgm(out),
+            ImageFormat::Png => self.write_png(out),
+        }
+    }
+
+    // Writes the image in the format given by the file's extension
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let format = ImageFormat::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{}: unknown image type", path.display()),
+            )
+        })?;
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write(&mut out, format)?;
+        out.flush()
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn to_image<F>(&self, color: F) -> Image
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let xb = self.x_bounds();
+        let yb = self.y_bounds();
+        Image {
+            width: (xb.end - xb.start) as usize,
+            height: (yb.end - yb.start) as usize,
+            pixels: self.iter().map(color).collect(),
+        }
+    }
+
+    pub fn save_image<P, F>(&self, path: P, color: F) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+        F: Fn(&T) -> Rgb,
+    {
+        self.to_image(color).save(path)
+    }
+}
+
+impl<T> InfiniteGrid<T> {
+    // Covers the grid's bounds, with unset cells getting the default value.
+    // Like print, this honors flip_y.
+    pub fn to_image<F>(&self, color: F) -> Image
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let xb = self.x_bounds();
+        let yb = self.y_bounds();
+        let rows: Vec<i64> = if self.y_flipped() {
+            yb.rev().collect()
+        } else {
+            yb.collect()
+        };
+        let mut pixels = Vec::with_capacity(rows.len() * (xb.end - xb.start) as usize);
+        for y in rows.iter() {
+            for x in xb.clone() {
+                pixels.push(color(self.get_ref(x, *y)));
+            }
+        }
+        Image {
+            width: (xb.end - xb.start) as usize,
+            height: rows.len(),
+            pixels,
+        }
+    }
+
+    pub fn save_image<P, F>(&self, path: P, color: F) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+        F: Fn(&T) -> Rgb,
+    {
+        self.to_image(color).save(path)
+    }
+}
+
+enum FrameOutput {
+    Sequence {
+        dir: PathBuf,
+        prefix: String,
+        format: ImageFormat,
+    },
+    Gif {
+        path: PathBuf,
+        delay: u16,
+        encoder: Option<(gif::Encoder<BufWriter<File>>, usize, usize)>,
+    },
+}
+
+// Records the steps of a simulation, either as numbered image files or as
+// an animated GIF. GIF frames all have the size of the first one; smaller
+// frames are drawn in the top left corner, larger ones are an error.
+pub struct FrameRecorder {
+    output: FrameOutput,
+    frames: usize,
+}
+
+impl FrameRecorder {
+    // Frames go to <dir>/<prefix>00000.<ext>, <prefix>00001.<ext>, ...
+    pub fn sequence<P: AsRef<Path>>(dir: P, prefix: &str, format: ImageFormat) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            output: FrameOutput::Sequence {
+                dir: dir.as_ref().into(),
+                prefix: prefix.into(),
+                format,
+            },
+            frames: 0,
+        })
+    }
+
+    // An endlessly looping GIF, showing each frame for `delay` hundredths of
+    // a second. The file is created when the first frame is added.
+    pub fn gif<P: AsRef<Path>>(path: P, delay: u16) -> Self {
+        Self {
+            output: FrameOutput::Gif {
+                path: path.as_ref().into(),
+                delay,
+                encoder: None,
+            },
+            frames: 0,
+        }
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn add(&mut self, image: &Image) -> io::Result<()> {
+        match &mut self.output {
+            FrameOutput::Sequence {
+                dir,
+                prefix,
+                format,
+            } => {
+                let name = format!("{prefix}{:05}.{}", self.frames, format.extension());
+                image.save(dir.join(name))?;
+            }
+            FrameOutput::Gif {
+                path,
+                delay,
+                encoder,
+            } => {
+                let (gif_width, gif_height) = gif_size(image)?;
+                if encoder.is_none() {
+                    let file = BufWriter::new(File::create(&*path)?);
+                    let mut enc = gif::Encoder::new(file, gif_width, gif_height, &[])
+                        .map_err(io::Error::other)?;
+                    enc.set_repeat(gif::Repeat::Infinite)
+                        .map_err(io::Error::other)?;
+                    *encoder = Some((enc, image.width, image.height));
+                }
+                let (enc, width, height) = encoder.as_mut().unwrap();
+                if image.width > *width || image.height > *height {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "frame is {}x{}, bigger than the first frame ({width}x{height})",
+                            image.width, image.height
+                        ),
+                    ));
+                }
+                let mut frame = gif::Frame::from_rgb(gif_width, gif_height, &image.rgb_bytes());
+                frame.delay = *delay;
+                enc.write_frame(&frame).map_err(io::Error::other)?;
+            }
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    // Finishes the file for a GIF (dropping the recorder does too, but
+    // without reporting errors)
+    pub fn finish(self) -> io::Result<()> {
+        if let FrameOutput::Gif {
+            encoder: Some((enc, _, _)),
+            ..
+        } = self.output
+        {
+            let mut file = enc.into_inner().map_err(io::Error::other)?;
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+// GIF sizes are 16 bit
+fn gif_size(image: &Image) -> io::Result<(u16, u16)> {
+    match (u16::try_from(image.width), u16::try_from(image.height)) {
+        (Ok(w), Ok(h)) => Ok((w, h)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "frame is {}x{}, too big for a GIF (at most 65535x65535)",
+                image.width, image.height
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_images() {
+        let mut grid: Grid<u8> = Grid::new(0, 0, 2, 1, 0);
+        grid.set(1, 0, 255);
+        let image = grid.to_image(|v| [*v, 0, *v / 2]);
+        assert_eq!((image.width, image.height), (3, 2));
+        assert_eq!(image.get(1, 0), [255, 0, 127]);
+
+        let mut out = Vec::new();
+        image.write_ppm(&mut out).unwrap();
+        assert!(out.starts_with(b"P6\n3 2\n255\n"));
+        assert_eq!(out.len(), 11 + 18);
+        out.clear();
+        image.write_pgm(&mut out).unwrap();
+        assert_eq!(&out[11..], &[0, 90, 0, 0, 0, 0]);
+        out.clear();
+        image.write_png(&mut out).unwrap();
+        assert!(out.starts_with(b"\x89PNG"));
+
+        let scaled = image.scaled(2);
+        assert_eq!((scaled.width, scaled.height), (6, 4));
+        assert_eq!(scaled.get(3, 1), [255, 0, 127]);
+        assert_eq!(Image::new(0, 2, [0; 3]).scaled(3), Image::new(0, 6, [0; 3]));
+
+        let mut inf = InfiniteGrid::new(false);
+        inf.set(0, 0, true);
+        inf.set(1, 2, true);
+        inf.flip_y(true);
+        let image = inf.to_image(|v| if *v { [255; 3] } else { [0; 3] });
+        assert_eq!((image.width, image.height), (2, 3));
+        assert_eq!((image.get(1, 0), image.get(0, 2)), ([255; 3], [255; 3]));
+
+        let dir = TempDir::new("render");
+        grid.save_image(dir.join("grid.png"), |v| [*v; 3]).unwrap();
+        assert!(fs::metadata(dir.join("grid.png")).unwrap().len() > 0);
+        assert!(grid.save_image(dir.join("grid.bmp"), |v| [*v; 3]).is_err());
+    }
+
+    #[test]
+    fn test_frames() {
+        let dir = TempDir::new("frames");
+        let mut grid: Grid<bool> = Grid::new(0, 0, 3, 3, false);
+        let color = |v: &bool| if *v { [255, 255, 0] } else { [0, 0, 64] };
+
+        let mut seq = FrameRecorder::sequence(dir.join("seq"), "step", ImageFormat::Pgm).unwrap();
+        let mut anim = FrameRecorder::gif(dir.join("anim.gif"), 10);
+        for i in 0..4 {
+            grid.set(i, i, true);
+            seq.add(&grid.to_image(color)).unwrap();
+            anim.add(&grid.to_image(color).scaled(2)).unwrap();
+        }
+        assert!(anim.add(&Image::new(9, 9, [0; 3])).is_err());
+        assert!(anim.add(&Image::new(70000, 1, [0; 3])).is_err());
+        assert_eq!(anim.frames(), 4);
+        anim.finish().unwrap();
+        seq.finish().unwrap();
+        assert!(dir.join("seq/step00003.pgm").is_file());
+        let gif = fs::read(dir.join("anim.gif")).unwrap();
+        assert!(gif.starts_with(b"GIF89a") && gif.ends_with(b";"));
+    }
+}
@@ -0,0 +1,496 @@
+use crate::read::{day_from_exe, InputSource};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+// Known-good answers for one day, stored one per line as "part1: <answer>".
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Answers {
+    parts: BTreeMap<u8, String>,
+}
+
+impl Answers {
+    // A missing file is just an empty set of answers.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+        let parts = text
+            .lines()
+            .filter_map(|line| {
+                let (key, val) = line.split_once(':')?;
+                let part = key.trim().strip_prefix("part")?.parse().ok()?;
+                Some((part, val.trim().to_string()))
+            })
+            .collect();
+        Ok(Self { parts })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let text: String = self
+            .parts
+            .iter()
+            .map(|(part, val)| format!("part{part}: {val}\n"))
+            .collect();
+        fs::write(path, text)
+    }
+
+    pub fn get(&self, part: u8) -> Option<&str> {
+        self.parts.get(&part).map(|s| s.as_str())
+    }
+
+    pub fn set(&mut self, part: u8, answer: &str) {
+        self.parts.insert(part, answer.into());
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "ok"),
+            Status::Fail { expected } => write!(f, "FAIL (expected {expected})"),
+            Status::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PartResult {
+    pub part: u8,
+    pub answer: String,
+    pub elapsed: Duration,
+    pub status: Status,
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let micros = d.as_micros();
+    if micros < 1000 {
+        format!("{micros}µs")
+    } else if micros < 1_000_000 {
+        format!("{:.2}ms", micros as f64 / 1000.0)
+    } else {
+        format!("{:.3}s", d.as_secs_f64())
+    }
+}
+
+// Runs and times the parts for a day, checking each answer against the
+// day's answers file (dayNN.answers in the current directory by default).
+//
+//   let mut runner = Runner::for_exe();
+//   runner.part(1, || part1(&input));
+//   runner.part(2, || part2(&input));
+//   runner.finish();
+pub struct Runner {
+    day: Option<u32>,
+    answers_path: PathBuf,
+    answers: Answers,
+    results: Vec<PartResult>,
+}
+
+impl Runner {
+    pub fn new(day: u32) -> Self {
+        Self::with_answers_file(Some(day), format!("day{day:02}.answers"))
+    }
+
+    // Like new() but with the day inferred from a dayNN executable name. If
+    // there isn't one, answers go in answers.txt.
+    pub fn for_exe() -> Self {
+        match day_from_exe() {
+            Some(day) => Self::new(day),
+            None => Self::with_answers_file(None, "answers.txt"),
+        }
+    }
+
+    pub fn with_answers_file<P: AsRef<Path>>(day: Option<u32>, path: P) -> Self {
+        let answers_path: PathBuf = path.as_ref().into();
+        let answers = Answers::load(&answers_path)
+            .unwrap_or_else(|e| panic!("{}: {e}", answers_path.display()));
+        Self {
+            day,
+            answers_path,
+            answers,
+            results: Vec::new(),
+        }
+    }
+
+    pub fn day(&self) -> Option<u32> {
+        self.day
+    }
+
+    pub fn part<R: Display, F: FnOnce() -> R>(&mut self, part: u8, func: F) -> R {
+        let start = Instant::now();
+        let result = func();
+        let elapsed = start.elapsed();
+        let answer = result.to_string();
+        let status = match self.answers.get(part) {
+            Some(expected) if expected == answer => Status::Pass,
+            Some(expected) => Status::Fail {
+                expected: expected.into(),
+            },
+            None => Status::Unknown,
+        };
+        self.results.push(PartResult {
+            part,
+            answer,
+            elapsed,
+            status,
+        });
+        result
+    }
+
+    pub fn results(&self) -> &[PartResult] {
+        &self.results
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.results.iter().map(|r| r.elapsed).sum()
+    }
+
+    pub fn all_passed(&self) -> bool {
+        self.results.iter().all(|r| r.status == Status::Pass)
+    }
+
+    pub fn any_failed(&self) -> bool {
+        self.results
+            .iter()
+            .any(|r| matches!(r.status, Status::Fail { .. }))
+    }
+
+    pub fn report(&self) -> String {
+        let width = self
+            .results
+            .iter()
+            .map(|r| r.answer.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+        let mut s = format!("Part  {:width$}  {:>10}  Status\n", "Answer", "Time");
+        for r in &self.results {
+            s.push_str(&format!(
+                "{:>4}  {:width$}  {:>10}  {}\n",
+                r.part,
+                r.answer,
+                format_duration(r.elapsed),
+                r.status
+            ));
+        }
+        s
+    }
+
+    // Saves the answers of any parts that don't have one yet. Answers that
+    // disagree with the stored ones are left alone; fix those by hand.
+    pub fn record(&mut self) -> io::Result<usize> {
+        let mut count = 0;
+        for r in self.results.iter_mut() {
+            if r.status == Status::Unknown {
+                self.answers.set(r.part, &r.answer);
+                r.status = Status::Pass;
+                count += 1;
+            }
+        }
+        if count > 0 {
+            self.answers.save(&self.answers_path)?;
+        }
+        Ok(count)
+    }
+
+    // Prints the report. With AOC_RECORD set in the environment, new answers
+    // are recorded first.
+    pub fn finish(mut self) {
+        if std::env::var_os("AOC_RECORD").is_some_and(|v| !v.is_empty()) {
+            match self.record() {
+                Ok(n) if n > 0 => println!(
+                    "Recorded {n} new answer(s) in {}",
+                    self.answers_path.display()
+                ),
+                Ok(_) => {}
+                Err(e) => eprintln!("{}: {e}", self.answers_path.display()),
+            }
+        }
+        print!("{}", self.report());
+    }
+}
+
+// One day's solution, as registered with the multi-day runner. Usually
+// created with the day! macro rather than implemented by hand.
+pub trait Day {
+    fn day(&self) -> u32;
+    fn run(&self, lines: Vec<String>, runner: &mut Runner);
+}
+
+// Wraps a day module's part1/part2 functions as a Box<dyn Day>. The day
+// number is taken from the module name (day05 -> 5), the same way input
+// files are found from dayNN executable names. The input is parsed with
+// input_from_iter, or with the given function (taking &[String]) if there
+// is one:
+//
+//   day!(day05)
+//   day!(solutions::day12, |lines: &[String]| Grid::from_input(lines, Cell::Empty, 1))
+#[macro_export]
+macro_rules! day {
+    ($m:path) => {
+        $crate::day!($m, |lines: &[String]| {
+            $crate::read::input_from_iter(lines.iter().cloned())
+        })
+    };
+    ($m:path, $parse:expr) => {{
+        struct D;
+        impl $crate::runner::Day for D {
+            fn day(&self) -> u32 {
+                let name = stringify!($m);
+                let name = name.rsplit("::").next().unwrap().trim();
+                $crate::read::day_from_name(name)
+                    .unwrap_or_else(|| panic!("can't get a day number from {name}"))
+            }
+            fn run(&self, lines: Vec<String>, runner: &mut $crate::runner::Runner) {
+                #[allow(clippy::redundant_closure_call)]
+                let input = ($parse)(&lines);
+                use $m as day_mod;
+                runner.part(1, || day_mod::part1(&input));
+                runner.part(2, || day_mod::part2(&input));
+            }
+        }
+        Box::new(D) as Box<dyn $crate::runner::Day>
+    }};
+}
+
+// Builds the list of days for run_days: days![day01, day02, day03]
+#[macro_export]
+macro_rules! days {
+    ($($m:path $(=> $parse:expr)?),* $(,)?) => {
+        vec![$($crate::day!($m $(, $parse)?)),*]
+    };
+}
+
+#[derive(Debug, PartialEq)]
+enum DayStatus {
+    Pass,
+    Fail,
+    Unknown,
+    NoInput,
+}
+
+impl Display for DayStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DayStatus::Pass => write!(f, "ok"),
+            DayStatus::Fail => write!(f, "FAIL"),
+            DayStatus::Unknown => write!(f, "?"),
+            DayStatus::NoInput => write!(f, "no input"),
+        }
+    }
+}
+
+// Parses day selections like "3", "5-7" or "all". No selections means all.
+fn selected_days(args: &[String]) -> Result<Option<Vec<u32>>, String> {
+    let mut days = Vec::new();
+    for arg in args {
+        if arg == "all" {
+            return Ok(None);
+        }
+        let (from, to) = arg.split_once('-').unwrap_or((arg, arg));
+        match (from.parse::<u32>(), to.parse::<u32>()) {
+            (Ok(from), Ok(to)) if from <= to => days.extend(from..=to),
+            _ => return Err(format!("invalid day selection: {arg}")),
+        }
+    }
+    Ok(if days.is_empty() { None } else { Some(days) })
+}
+
+// A main() for a binary running many days:
+//
+//   fn main() -> ExitCode {
+//       run_days(days![day01, day02, day03])
+//   }
+//
+// Command line arguments select days ("3", "5-7", "all"; default all).
+// "--inputs DIR" says where the dayNN.input and dayNN.answers files are
+// (default the current directory). Each day's parts are timed and checked
+// as with Runner, followed by a summary table with the total time.
+pub fn run_days(mut days: Vec<Box<dyn Day>>) -> ExitCode {
+    let mut dir = PathBuf::from(".");
+    let mut sel_args = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--inputs" {
+            match args.next() {
+                Some(d) => dir = d.into(),
+                None => {
+                    eprintln!("--inputs requires a directory");
+                    return ExitCode::FAILURE;
+                }
+            }
+        } else {
+            sel_args.push(arg);
+        }
+    }
+    let selected = match selected_days(&sel_args) {
+        Ok(sel) => sel,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    days.sort_by_key(|d| d.day());
+    if let Some(sel) = &selected {
+        for day in sel.iter().filter(|n| !days.iter().any(|d| d.day() == **n)) {
+            eprintln!("Day {day} is not registered");
+        }
+    }
+
+    let mut summary: Vec<(u32, Vec<Duration>, Duration, DayStatus)> = Vec::new();
+    for d in days
+        .iter()
+        .filter(|d| selected.as_ref().is_none_or(|sel| sel.contains(&d.day())))
+    {
+        let day = d.day();
+        println!("Day {day}");
+        let source = InputSource::new()
+            .use_args(false)
+            .use_stdin(false)
+            .env_var(None)
+            .dir(&dir)
+            .pattern("day{day:02}.input")
+            .add_pattern("day{day}.input")
+            .day(day);
+        let lines = match source
+            .open()
+            .and_then(|l| l.collect::<io::Result<Vec<_>>>())
+        {
+            Ok(lines) => lines,
+            Err(e) => {
+                println!("{e}\n");
+                summary.push((day, Vec::new(), Duration::ZERO, DayStatus::NoInput));
+                continue;
+            }
+        };
+        let mut runner =
+            Runner::with_answers_file(Some(day), dir.join(format!("day{day:02}.answers")));
+        let start = Instant::now();
+        d.run(lines, &mut runner);
+        let elapsed = start.elapsed();
+        println!("{}", runner.report());
+        let status = if runner.any_failed() {
+            DayStatus::Fail
+        } else if runner.all_passed() {
+            DayStatus::Pass
+        } else {
+            DayStatus::Unknown
+        };
+        let times = runner.results().iter().map(|r| r.elapsed).collect();
+        summary.push((day, times, elapsed, status));
+    }
+
+    println!(
+        "Day  {:>10}  {:>10}  {:>10}  Status",
+        "Part 1", "Part 2", "Total"
+    );
+    for (day, times, elapsed, status) in &summary {
+        let part = |idx: usize| {
+            times
+                .get(idx)
+                .map(|t| format_duration(*t))
+                .unwrap_or_default()
+        };
+        println!(
+            "{day:>3}  {:>10}  {:>10}  {:>10}  {status}",
+            part(0),
+            part(1),
+            format_duration(*elapsed)
+        );
+    }
+    let total: Duration = summary.iter().map(|s| s.2).sum();
+    let count = |st: DayStatus| summary.iter().filter(|s| s.3 == st).count();
+    println!(
+        "Total: {} ({} ok, {} failed, {} unknown, {} without input)",
+        format_duration(total),
+        count(DayStatus::Pass),
+        count(DayStatus::Fail),
+        count(DayStatus::Unknown),
+        count(DayStatus::NoInput),
+    );
+    if summary
+        .iter()
+        .any(|s| s.3 == DayStatus::Fail || s.3 == DayStatus::NoInput)
+    {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_runner() {
+        let tmp = TempDir::new("answers");
+        let answers = tmp.join("answers");
+        fs::write(&answers, "part1: 42\n").unwrap();
+
+        let mut runner = Runner::with_answers_file(Some(1), &answers);
+        assert_eq!(runner.part(1, || 6 * 7), 42);
+        runner.part(2, || "abc");
+        assert_eq!(runner.results()[0].status, Status::Pass);
+        assert_eq!(runner.results()[1].status, Status::Unknown);
+        assert!(!runner.all_passed());
+        assert_eq!(runner.record().unwrap(), 1);
+        assert!(runner.all_passed());
+
+        let mut runner = Runner::with_answers_file(Some(1), &answers);
+        runner.part(1, || 41);
+        runner.part(2, || "abc");
+        assert!(runner.any_failed());
+        assert_eq!(
+            runner.results()[0].status,
+            Status::Fail {
+                expected: "42".into()
+            }
+        );
+        assert_eq!(runner.results()[1].status, Status::Pass);
+        assert!(runner.report().contains("FAIL (expected 42)"));
+    }
+
+    mod day07 {
+        pub fn part1(input: &[i64]) -> i64 {
+            input.iter().sum()
+        }
+        pub fn part2(input: &[i64]) -> usize {
+            input.len()
+        }
+    }
+
+    #[test]
+    fn test_day_registry() {
+        let days = days![day07, day07 => |lines: &[String]| vec![lines.len() as i64]];
+        assert_eq!(days[0].day(), 7);
+        let mut runner = Runner::with_answers_file(Some(7), "/nonexistent/answers");
+        days[0].run(vec!["1".into(), "2".into()], &mut runner);
+        days[1].run(vec!["1".into(), "2".into()], &mut runner);
+        let answers: Vec<&str> = runner.results().iter().map(|r| r.answer.as_str()).collect();
+        assert_eq!(answers, vec!["3", "2", "2", "1"]);
+
+        assert_eq!(selected_days(&[]), Ok(None));
+        assert_eq!(
+            selected_days(&["1".into(), "5-7".into()]),
+            Ok(Some(vec![1, 5, 6, 7]))
+        );
+        assert!(selected_days(&["x".into()]).is_err());
+        assert!(selected_days(&["7-5".into()]).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const TEMPLATES: &[(&str, &str)] = &[
+    ("basic", include_str!("../templates/basic.rs")),
+    ("grid", include_str!("../templates/grid.rs")),
+    ("fromstr", include_str!("../templates/fromstr.rs")),
+    (
+        "fromstr_regex",
+        include_str!("../templates/fromstr_regex.rs"),
+    ),
+    (
+        "fromstr_pattern",
+        include_str!("../templates/fromstr_pattern.rs"),
+    ),
+];
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    UnknownTemplate(String),
+    Exists(PathBuf),
+    AlreadyRegistered(String),
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::UnknownTemplate(name) => write!(
+                f,
+                "Unknown template {name}. Choose one of: {}",
+                TEMPLATES
+                    .iter()
+                    .map(|(n, _)| *n)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            ScaffoldError::Exists(path) => {
+                write!(f, "{} already exists; not overwriting", path.display())
+            }
+            ScaffoldError::AlreadyRegistered(name) => {
+                write!(f, "A [[bin]] named {name} is already in the manifest")
+            }
+            ScaffoldError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+        }
+    }
+}
+
+impl Error for ScaffoldError {}
+
+pub fn template(name: &str) -> Result<&'static str, ScaffoldError> {
+    TEMPLATES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, t)| *t)
+        .ok_or_else(|| ScaffoldError::UnknownTemplate(name.into()))
+}
+
+// Whether one of the manifest's [[bin]] tables is named `name`
+fn bin_registered(manifest: &str, name: &str) -> bool {
+    let mut in_bin = false;
+    manifest.lines().map(str::trim).any(|l| {
+        if l.starts_with('[') {
+            in_bin = l == "[[bin]]";
+            return false;
+        }
+        in_bin
+            && l.strip_prefix("name")
+                .and_then(|l| l.trim_start().strip_prefix('='))
+                .is_some_and(|v| v.trim().trim_matches('"') == name)
+    })
+}
+
+// Creates <dir>/dayNN.rs from the named template and an empty
+// <dir>/dayNN.testinput next to it, and adds a [[bin]] entry for dayNN to the
+// manifest. Nothing is written if either file already exists or the bin is
+// already registered. Returns the paths created.
+pub fn scaffold(
+    template_name: &str,
+    day: u32,
+    dir: &Path,
+    manifest_path: &Path,
+) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let source = template(template_name)?;
+    let name = format!("day{day:02}");
+    let src_path = dir.join(format!("{name}.rs"));
+    let test_path = dir.join(format!("{name}.testinput"));
+    for path in [&src_path, &test_path] {
+        if path.exists() {
+            return Err(ScaffoldError::Exists(path.clone()));
+        }
+    }
+    let manifest = fs::read_to_string(manifest_path)
+        .map_err(|e| ScaffoldError::Io(manifest_path.into(), e))?;
+    if bin_registered(&manifest, &name) {
+        return Err(ScaffoldError::AlreadyRegistered(name));
+    }
+
+    // The bin path is relative to the manifest's directory, if possible
+    let bin_path = manifest_path
+        .parent()
+        .and_then(|base| src_path.strip_prefix(base).ok())
+        .unwrap_or(&src_path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let mut manifest = manifest;
+    if !manifest.is_empty() && !manifest.ends_with('\n') {
+        manifest.push('\n');
+    }
+    manifest.push_str(&format!(
+        "\n[[bin]]\nname = \"{name}\"\npath = \"{bin_path}\"\n"
+    ));
+
+    fs::create_dir_all(dir).map_err(|e| ScaffoldError::Io(dir.into(), e))?;
+    fs::write(&src_path, source.replace("dayNN", &name))
+        .map_err(|e| ScaffoldError::Io(src_path.clone(), e))?;
+    fs::write(&test_path, "").map_err(|e| ScaffoldError::Io(test_path.clone(), e))?;
+    fs::write(manifest_path, manifest).map_err(|e| ScaffoldError::Io(manifest_path.into(), e))?;
+    Ok(vec![src_path, test_path])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_scaffold() {
+        let tmp = TempDir::new("scaffold");
+        let manifest = tmp.join("Cargo.toml");
+        fs::write(&manifest, "[package]\nname = \"day06\"\n").unwrap();
+
+        let created = scaffold("grid", 5, &tmp.join("src"), &manifest).unwrap();
+        assert_eq!(created[0], tmp.join("src/day05.rs"));
+        let src = fs::read_to_string(&created[0]).unwrap();
+        assert!(src.contains("fn day05_test()") && !src.contains("dayNN"));
+        let toml = fs::read_to_string(&manifest).unwrap();
+        assert!(toml.ends_with("[[bin]]\nname = \"day05\"\npath = \"src/day05.rs\"\n"));
+
+        assert!(matches!(
+            scaffold("basic", 5, &tmp.join("src"), &manifest),
+            Err(ScaffoldError::Exists(_))
+        ));
+        assert!(matches!(
+            scaffold("basic", 5, &tmp.join("other"), &manifest),
+            Err(ScaffoldError::AlreadyRegistered(_))
+        ));
+        assert!(matches!(
+            scaffold("nope", 6, &tmp.join("src"), &manifest),
+            Err(ScaffoldError::UnknownTemplate(_))
+        ));
+        assert_eq!(fs::read_to_string(&manifest).unwrap(), toml);
+
+        // the package name isn't a bin
+        scaffold("basic", 6, &tmp.join("src"), &manifest).unwrap();
+    }
+}
@@ -127,7 +127,7 @@ impl<
         let mut last_tick = Instant::now();
         loop {
             if self.run_state != RunState::Run {
-                terminal.draw(|f| self.ui::<B>(f))?;
+                terminal.draw(|f| self.ui(f))?;
             }
             self.breakpoint = false;
 
@@ -178,14 +178,14 @@ impl<
                         }
                         KeyCode::Char('r') => {
                             self.cmd_run();
-                            terminal.draw(|f| self.ui::<B>(f))?;
+                            terminal.draw(|f| self.ui(f))?;
                         }
                         KeyCode::Char('w') => self.cmd_walk(),
                         KeyCode::Char('p') => self.cmd_pause(),
                         KeyCode::Char('h') => {
                             self.hex_mode = !self.hex_mode;
                             if self.run_state == RunState::Run {
-                                terminal.draw(|f| self.ui::<B>(f))?;
+                                terminal.draw(|f| self.ui(f))?;
                             }
                         }
                         KeyCode::Char('b') => {
@@ -201,13 +201,9 @@ impl<
             if match self.run_state {
                 RunState::Run => true,
                 RunState::Pause => do_step,
-                RunState::Walk => {
-                    if last_tick.elapsed() >= self.walk_delay {
-                        last_tick = Instant::now();
-                        true
-                    } else {
-                        false
-                    }
+                RunState::Walk if last_tick.elapsed() >= self.walk_delay => {
+                    last_tick = Instant::now();
+                    true
                 }
                 _ => false,
             } {
@@ -252,7 +248,7 @@ impl<
         }
     }
 
-    fn ui<B: Backend>(&mut self, f: &mut Frame) {
+    fn ui(&mut self, f: &mut Frame) {
         let (statuspanel, sp_height) = self.render_statuspanel();
         let root = Layout::default()
             .direction(Direction::Horizontal)
@@ -262,8 +258,8 @@ impl<
             .direction(Direction::Vertical)
             .constraints([Constraint::Min(10), Constraint::Length(sp_height)].as_ref())
             .split(root[1]);
-        self.render_code::<B>(f, root[0]);
-        self.render_registers::<B>(f, rightside[0]);
+        self.render_code(f, root[0]);
+        self.render_registers(f, rightside[0]);
         f.render_widget(statuspanel, rightside[1]);
         if matches!(self.focus, Focus::BreakpointInput) {
             let rect = Rect::new(
@@ -272,11 +268,11 @@ impl<
                 rightside[1].width - 23,
                 3,
             );
-            self.render_pc_input::<B>(f, rect);
+            self.render_pc_input(f, rect);
         }
     }
 
-    fn render_statuspanel(&self) -> (List, u16) {
+    fn render_statuspanel(&self) -> (List<'_>, u16) {
         let items: Vec<ListItem> = vec![
             ListItem::new("[p] Pause           [q] Quit"),
             ListItem::new("[s] Step"),
@@ -300,7 +296,7 @@ impl<
         )
     }
 
-    fn render_pc_input<B: Backend>(&self, f: &mut Frame, rect: Rect) {
+    fn render_pc_input(&self, f: &mut Frame, rect: Rect) {
         let scroll = self.pc_input.visual_scroll((rect.width - 2) as usize);
         let input = Paragraph::new(self.pc_input.value())
             .scroll((0, scroll as u16))
@@ -324,7 +320,7 @@ impl<
         }
     }
 
-    fn token_list(&self, iter: &mut dyn Iterator<Item = Vec<Token<RegVal>>>) -> Vec<Line> {
+    fn token_list(&self, iter: &mut dyn Iterator<Item = Vec<Token<RegVal>>>) -> Vec<Line<'_>> {
         let rows: Vec<Vec<Span>> = iter
             .map(|row| {
                 row.iter()
@@ -365,7 +361,7 @@ impl<
             .collect()
     }
 
-    fn render_code<B: Backend>(&self, f: &mut Frame, rect: Rect) {
+    fn render_code(&self, f: &mut Frame, rect: Rect) {
         let mut itr = self
             .shell
             .vm
@@ -405,7 +401,7 @@ impl<
         f.render_stateful_widget(list, rect, &mut liststate);
     }
 
-    fn render_registers<B: Backend>(&self, f: &mut Frame, rect: Rect) {
+    fn render_registers(&self, f: &mut Frame, rect: Rect) {
         let mut itr = self
             .shell
             .vm
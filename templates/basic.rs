@@ -1,5 +1,6 @@
 use std::vec::Vec;
 use ya_advent_lib::read::read_input;
+use ya_advent_lib::runner::Runner;
 
 fn part1(input: &[String]) -> i64 {
     0
@@ -11,8 +12,10 @@ fn part2(input: &[String]) -> i64 {
 
 fn main() {
     let input: Vec<String> = read_input();
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    let mut runner = Runner::for_exe();
+    runner.part(1, || part1(&input));
+    runner.part(2, || part2(&input));
+    runner.finish();
 }
 
 #[cfg(test)]
@@ -1,6 +1,7 @@
 use std::str::FromStr;
 use std::vec::Vec;
 use ya_advent_lib::read::read_input;
+use ya_advent_lib::runner::Runner;
 
 struct Input {
 }
@@ -22,8 +23,10 @@ fn part2(input: &[Input]) -> i64 {
 
 fn main() {
     let input: Vec<Input> = read_input();
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    let mut runner = Runner::for_exe();
+    runner.part(1, || part1(&input));
+    runner.part(2, || part2(&input));
+    runner.finish();
 }
 
 #[cfg(test)]
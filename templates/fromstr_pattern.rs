@@ -0,0 +1,41 @@
+use std::vec::Vec;
+use ya_advent_lib::line_pattern;
+use ya_advent_lib::read::read_input;
+use ya_advent_lib::runner::Runner;
+
+line_pattern! {
+    "{name} {weight}" =>
+    struct Input {
+        name: String,
+        weight: i64,
+    }
+}
+
+fn part1(input: &[Input]) -> usize {
+    0
+}
+
+fn part2(input: &[Input]) -> usize {
+    0
+}
+
+fn main() {
+    let input: Vec<Input> = read_input();
+    let mut runner = Runner::for_exe();
+    runner.part(1, || part1(&input));
+    runner.part(2, || part2(&input));
+    runner.finish();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn dayNN_test() {
+        let input: Vec<Input> = test_input(include_str!("dayNN.testinput"));
+        assert_eq!(part1(&input), 0);
+        assert_eq!(part2(&input), 0);
+    }
+}
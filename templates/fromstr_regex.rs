@@ -3,6 +3,7 @@ use std::vec::Vec;
 use lazy_static::lazy_static;
 use regex::Regex;
 use ya_advent_lib::read::read_input;
+use ya_advent_lib::runner::Runner;
 
 struct Input {
     name: String,
@@ -38,8 +39,10 @@ fn part2(input: &[Input]) -> usize {
 
 fn main() {
     let input: Vec<Input> = read_input();
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    let mut runner = Runner::for_exe();
+    runner.part(1, || part1(&input));
+    runner.part(2, || part2(&input));
+    runner.finish();
 }
 
 #[cfg(test)]
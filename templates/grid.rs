@@ -1,6 +1,7 @@
 use std::vec::Vec;
-use ya_advent_lib::read::read_input;
 use ya_advent_lib::grid::Grid;
+use ya_advent_lib::read::read_input;
+use ya_advent_lib::runner::Runner;
 
 #[derive(Clone, Copy)]
 enum Cell {
@@ -29,8 +30,10 @@ fn part2(input: &[String]) -> i64 {
 
 fn main() {
     let input: Vec<String> = read_input();
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    let mut runner = Runner::for_exe();
+    runner.part(1, || part1(&input));
+    runner.part(2, || part2(&input));
+    runner.finish();
 }
 
 #[cfg(test)]